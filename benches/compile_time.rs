@@ -0,0 +1,170 @@
+//! Compares the compile time and the fully expanded size of a downstream crate
+//! deriving `StructArray` on a few hundred structs, with the current version
+//! and with a baseline version of this crate.
+//!
+//! The baseline is the git revision in the `STRUCT_ARRAY_BASELINE` environment
+//! variable, by default the 0.2.1 release. Its source is extracted with `git
+//! archive`, so this must be run from a git checkout.
+//!
+//! For each version, a fixture crate is generated in
+//! `target/compile_time/<version>`, and its dependencies are built. Then the
+//! fixture alone is checked `RUNS` times with `cargo check`, and the fastest
+//! run is reported. The expanded size is that of the output of `rustc
+//! -Zunpretty=expanded`, in which the macros invoked by the derives are also
+//! expanded. (This is an unstable option, so `RUSTC_BOOTSTRAP=1` is set.)
+//!
+//! Run with `cargo bench --bench compile_time`.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// The 0.2.1 release, before the `StructArray` trait.
+const DEFAULT_BASELINE: &str = "2584b509664f5e84cb8afeb5b42961999727977c";
+
+/// Number of structs of each kind in the fixture.
+const STRUCTS_PER_KIND: usize = 150;
+
+/// Number of timed runs of `cargo check` for each version.
+const RUNS: usize = 5;
+
+/// Returns the source of the fixture crate, which derives `StructArray` on
+/// `STRUCTS_PER_KIND` structs with named fields and as many tuple structs.
+fn fixture_source() -> String {
+    let mut source = String::from("#[macro_use]\nextern crate struct_array;\n");
+    for i in 0..STRUCTS_PER_KIND {
+        write!(source,
+               "
+#[derive(Clone, Copy, Debug, PartialEq, StructArray)]
+#[repr(C)]
+pub struct Vec3_{i} {{
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}}
+
+#[derive(Clone, Copy, Debug, PartialEq, StructArray)]
+#[repr(C)]
+pub struct Rgba{i}(pub u8, pub u8, pub u8, pub u8);
+",
+               i = i)
+            .unwrap();
+    }
+    source
+}
+
+/// Writes the fixture crate depending on the version of this crate at
+/// `struct_array`, and returns its directory.
+fn write_fixture(dir: &Path, struct_array: &Path) -> PathBuf {
+    fs::create_dir_all(dir.join("src")).unwrap();
+    let manifest = format!("[package]
+name = \"fixture\"
+version = \"0.0.0\"
+
+[dependencies]
+struct_array = {{ path = {:?} }}
+
+[workspace]
+",
+                           struct_array);
+    fs::write(dir.join("Cargo.toml"), manifest).unwrap();
+    fs::write(dir.join("src/lib.rs"), fixture_source()).unwrap();
+    dir.to_path_buf()
+}
+
+/// Extracts the baseline revision of this crate into `dir`.
+fn extract_baseline(repo: &Path, revision: &str, dir: &Path) {
+    if dir.exists() {
+        fs::remove_dir_all(dir).unwrap();
+    }
+    fs::create_dir_all(dir).unwrap();
+    let mut archive = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["archive", "--format=tar", revision])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run git");
+    let status = Command::new("tar")
+        .arg("-x")
+        .arg("-C")
+        .arg(dir)
+        .stdin(archive.stdout.take().unwrap())
+        .status()
+        .expect("failed to run tar");
+    assert!(archive.wait().unwrap().success() && status.success(),
+            "failed to extract the baseline revision {}",
+            revision);
+}
+
+/// Runs `cargo` with the given arguments in the fixture crate, and returns its
+/// standard output.
+fn cargo(fixture: &Path, args: &[&str]) -> Vec<u8> {
+    let output = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+        .arg(args[0])
+        .arg("--quiet")
+        .args(&args[1..])
+        .current_dir(fixture)
+        .env("RUSTC_BOOTSTRAP", "1")
+        .env("RUSTFLAGS", "--cap-lints=allow")
+        .stderr(Stdio::inherit())
+        .output()
+        .expect("failed to run cargo");
+    assert!(output.status.success(), "cargo {:?} failed", args);
+    output.stdout
+}
+
+/// Returns the duration of the fastest `cargo check` of the fixture crate
+/// alone.
+fn time_check(fixture: &Path) -> Duration {
+    cargo(fixture, &["check"]);
+    (0..RUNS)
+        .map(|_| {
+            // Rewriting the source makes cargo check the fixture again.
+            fs::write(fixture.join("src/lib.rs"), fixture_source()).unwrap();
+            let start = Instant::now();
+            cargo(fixture, &["check"]);
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+/// Returns the number of bytes, lines and `impl` items in the fully expanded
+/// source of the fixture crate.
+fn measure_expansion(fixture: &Path) -> (usize, usize, usize) {
+    let expanded = cargo(fixture, &["rustc", "--profile=check", "--", "-Zunpretty=expanded"]);
+    let expanded = String::from_utf8(expanded).unwrap();
+    let impls = expanded.match_indices("impl").filter(|&(i, _)| is_keyword_at(&expanded, i, 4)).count();
+    (expanded.len(), expanded.lines().count(), impls)
+}
+
+/// Returns whether the `len` bytes of `source` at `index` are a whole word.
+fn is_keyword_at(source: &str, index: usize, len: usize) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    !source[..index].chars().next_back().is_some_and(is_ident) &&
+        !source[index + len..].chars().next().is_some_and(is_ident)
+}
+
+fn main() {
+    let repo = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let work_dir = repo.join("target/compile_time");
+    let revision = env::var("STRUCT_ARRAY_BASELINE").unwrap_or_else(|_| DEFAULT_BASELINE.into());
+    let baseline_src = work_dir.join("baseline-src");
+    extract_baseline(repo, &revision, &baseline_src);
+
+    let versions = [("baseline", baseline_src.as_path()), ("current", repo)];
+    println!("{} structs deriving `StructArray`", 2 * STRUCTS_PER_KIND);
+    println!("{:<10} {:>12} {:>14} {:>10} {:>8}",
+             "version", "check (s)", "expanded (B)", "lines", "impls");
+    for &(version, struct_array) in &versions {
+        let fixture = write_fixture(&work_dir.join(version), struct_array);
+        let check = time_check(&fixture);
+        let (bytes, lines, impls) = measure_expansion(&fixture);
+        println!("{:<10} {:>12.2} {:>14} {:>10} {:>8}",
+                 version, check.as_secs_f64(), bytes, lines, impls);
+    }
+}
@@ -0,0 +1,227 @@
+//! Implementation details used by the code generated by the derives.
+
+use std::mem;
+use std::ptr::{self, NonNull};
+
+use {FieldConversionError, FlatStructArray, Marker, StructArray};
+
+mod convert;
+#[cfg(feature = "serde")]
+pub mod serde;
+
+/// Traits for the `#[struct_array(approx)]` option.
+#[cfg(feature = "approx")]
+pub mod approx {
+    pub use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+}
+
+/// Traits for the `#[struct_array(bytemuck)]` option.
+#[cfg(feature = "bytemuck")]
+pub mod bytemuck {
+    pub use bytemuck::{Pod, Zeroable};
+}
+
+/// Types for the `#[struct_array(glam)]` option.
+#[cfg(feature = "glam")]
+pub mod glam {
+    pub use glam::{DVec2, DVec3, DVec4, IVec2, IVec3, IVec4, UVec2, UVec3, UVec4, Vec2, Vec3, Vec3A,
+                   Vec4};
+}
+
+/// Types for the `#[struct_array(mint(...))]` option.
+#[cfg(feature = "mint")]
+pub mod mint {
+    pub use mint::{Point2, Point3, Vector2, Vector3, Vector4};
+}
+
+/// Types for the `#[struct_array(nalgebra)]` option.
+#[cfg(feature = "nalgebra")]
+pub mod nalgebra {
+    pub use nalgebra::{DMatrixView, DMatrixViewMut, Point, SVector, SVectorView, SVectorViewMut};
+}
+
+/// Traits for the `#[struct_array(num_traits)]` option.
+#[cfg(feature = "num-traits")]
+pub mod num_traits {
+    pub use num_traits::{Bounded, NumCast, One, ToPrimitive, Zero};
+}
+
+/// Panics (at compile time, when used in a constant) if the layout of the
+/// struct does not match the layout of its array.
+pub const fn assert_layout<S: StructArray>() {
+    assert!(mem::size_of::<S>() == mem::size_of::<S::Array>() &&
+                (mem::size_of::<S>() == 0 || mem::align_of::<S>() == mem::align_of::<S::Array>()),
+            "the layout of the struct must match the layout of its array");
+}
+
+/// Panics (at compile time, when used in a constant) if the layout of the
+/// struct does not match the layout of its flat array.
+pub const fn assert_flat_layout<S: FlatStructArray>() {
+    assert!(mem::size_of::<S>() == mem::size_of::<S::FlatArray>() &&
+                (mem::size_of::<S>() == 0 ||
+                     mem::align_of::<S>() == mem::align_of::<S::FlatArray>()),
+            "the layout of the struct must match the layout of its flat array");
+}
+
+/// Checks at compile time that the type of a skipped field implements
+/// `Marker`.
+pub const fn assert_marker<T: Marker>() {}
+
+/// Checks at compile time that `S` and `T` have valid layouts and the same
+/// length, so (since they also have the same element type) the same layout.
+pub fn assert_same_shape<S: StructArray, T: StructArray<Elem = S::Elem>>() {
+    let () = S::__ASSERT_LAYOUT;
+    let () = T::__ASSERT_LAYOUT;
+    const {
+        assert!(S::LEN == T::LEN, "the struct arrays must have the same length");
+    }
+}
+
+/// Panics (at compile time, when used in a constant) if the layout of the
+/// struct `S` does not match the layout of the array `A`.
+pub const fn assert_same_layout<S, A>() {
+    assert!(mem::size_of::<S>() == mem::size_of::<A>() &&
+                (mem::size_of::<S>() == 0 || mem::align_of::<S>() == mem::align_of::<A>()),
+            "the layout of the struct must match the layout of its array");
+}
+
+/// Converts the struct `S` into the array `A`, or the reverse, for the
+/// conversions generated by `StructArrayConvert`, which do not rely on the
+/// `StructArray` trait.
+///
+/// # Safety
+///
+/// One of `S` and `A` must be a struct accepted by the derives, and the other
+/// its array (or its flat array, if it has the `flatten` option). Its skipped
+/// fields must implement `Marker`.
+pub unsafe fn transmute<S, A>(s: S) -> A {
+    const { assert_same_layout::<S, A>() }
+    let s = mem::ManuallyDrop::new(s);
+    ptr::read(cast::<S, _, A>(&*s))
+}
+
+/// Views a reference to the struct `S` as a reference to the array `A`, or
+/// the reverse.
+///
+/// # Safety
+///
+/// As for `transmute`.
+pub unsafe fn transmute_ref<S, A>(s: &S) -> &A {
+    const { assert_same_layout::<S, A>() }
+    &*cast::<S, _, A>(s)
+}
+
+/// Views a mutable reference to the struct `S` as a mutable reference to the
+/// array `A`, or the reverse.
+///
+/// # Safety
+///
+/// As for `transmute`.
+pub unsafe fn transmute_mut<S, A>(s: &mut S) -> &mut A {
+    const { assert_same_layout::<S, A>() }
+    &mut *cast_mut::<S, _, A>(s)
+}
+
+/// Returns a slice of the `N` elements of the struct `S`.
+///
+/// # Safety
+///
+/// As for `transmute`, with `[E; N]` as the array.
+pub unsafe fn as_slice<S, E, const N: usize>(s: &S) -> &[E] {
+    transmute_ref::<S, [E; N]>(s)
+}
+
+/// Returns a mutable slice of the `N` elements of the struct `S`.
+///
+/// # Safety
+///
+/// As for `transmute`, with `[E; N]` as the array.
+pub unsafe fn as_mut_slice<S, E, const N: usize>(s: &mut S) -> &mut [E] {
+    transmute_mut::<S, [E; N]>(s)
+}
+
+/// Views a slice of `N` elements as a reference to the struct `S`.
+///
+/// # Safety
+///
+/// As for `transmute`, with `[E; N]` as the array.
+///
+/// # Panics
+///
+/// Panics if the `len()` of the slice is not `N`.
+pub unsafe fn from_slice<E, S, const N: usize>(slice: &[E]) -> &S {
+    assert_eq!(slice.len(), N);
+    transmute_ref::<[E; N], S>(&*(slice.as_ptr() as *const [E; N]))
+}
+
+/// Views a mutable slice of `N` elements as a mutable reference to the struct
+/// `S`.
+///
+/// # Safety
+///
+/// As for `transmute`, with `[E; N]` as the array.
+///
+/// # Panics
+///
+/// Panics if the `len()` of the slice is not `N`.
+pub unsafe fn from_slice_mut<E, S, const N: usize>(slice: &mut [E]) -> &mut S {
+    assert_eq!(slice.len(), N);
+    transmute_mut::<[E; N], S>(&mut *(slice.as_mut_ptr() as *mut [E; N]))
+}
+
+/// Casts a pointer to (part of) the struct array `S`, or to its fields, to a
+/// pointer to `U`.
+///
+/// If `S` is zero-sized, which is the case for struct arrays with zero fields
+/// or zero-sized fields, no bytes are ever accessed through the pointer, but
+/// it may not be aligned for `U` (e.g. a struct with no fields has an
+/// alignment of 1, while `[f32; 0]` has an alignment of 4), so a dangling
+/// pointer aligned for `U` is returned instead.
+pub fn cast<S, T, U>(ptr: *const T) -> *const U {
+    if mem::size_of::<S>() == 0 {
+        NonNull::dangling().as_ptr()
+    } else {
+        ptr as *const U
+    }
+}
+
+/// Casts a mutable pointer like `cast`.
+pub fn cast_mut<S, T, U>(ptr: *mut T) -> *mut U {
+    if mem::size_of::<S>() == 0 {
+        NonNull::dangling().as_ptr()
+    } else {
+        ptr as *mut U
+    }
+}
+
+/// Returns the number of structs in a flat slice of `flat_len` fields.
+///
+/// # Panics
+///
+/// Panics if `flat_len` is not a multiple of `S::LEN`, or if `S::LEN` is zero
+/// and `flat_len` is not.
+pub fn flat_to_struct_len<S: StructArray>(flat_len: usize) -> usize {
+    if S::LEN == 0 {
+        assert_eq!(flat_len, 0);
+        0
+    } else {
+        assert_eq!(flat_len % S::LEN, 0);
+        flat_len / S::LEN
+    }
+}
+
+/// Aborts the process if dropped, which happens only while unwinding, when
+/// the value is not forgotten after the code that must not panic.
+pub struct AbortOnPanic;
+
+impl Drop for AbortOnPanic {
+    fn drop(&mut self) {
+        ::std::process::abort();
+    }
+}
+
+/// Returns the error for a value that could not be converted to the type of
+/// the given field.
+pub fn field_conversion_error(field: &'static str) -> FieldConversionError {
+    FieldConversionError { field }
+}
@@ -0,0 +1,252 @@
+//! Implementations of the standard library conversion traits for the derives.
+//!
+//! Coherence rules out blanket implementations of these traits, so they are
+//! implemented for each struct. Rather than generating each implementation,
+//! the derives invoke the macros in this module, which keeps the expansion of
+//! the derives small. The implementations do not rely on the `StructArray`
+//! trait, which only `#[derive(StructArray)]` implements, so
+//! `StructArrayDeref` and `StructArrayConvert` can be derived on their own.
+//!
+//! The macros take the generics of the implementations in brackets: the
+//! generics of the struct, the same with the lifetime `'a` added for
+//! implementations on references, and the `where` clause of the struct.
+
+/// Implements `Deref` and `DerefMut` to the array.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_struct_array_deref {
+    ([$($impl_generics:tt)*] $ty:ty, [$elem:ty; $len:expr], [$($where_clause:tt)*]) => {
+        impl $($impl_generics)* ::std::ops::Deref for $ty $($where_clause)* {
+            type Target = [$elem; $len];
+
+            fn deref(&self) -> &[$elem; $len] {
+                unsafe { $crate::__private::transmute_ref(self) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::ops::DerefMut for $ty $($where_clause)* {
+            fn deref_mut(&mut self) -> &mut [$elem; $len] {
+                unsafe { $crate::__private::transmute_mut(self) }
+            }
+        }
+    };
+}
+
+/// Implements the conversions to/from arrays and slices. `$from_slice_doc` is
+/// the documentation of the conversions from slices, which panic if the
+/// length is wrong.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_struct_array_convert {
+    ([$($impl_generics:tt)*] [$($impl_generics_a:tt)*] $a:lifetime, $ty:ty,
+     [$elem:ty; $len:expr], $from_slice_doc:expr, [$($where_clause:tt)*]) => {
+        impl $($impl_generics)* ::std::convert::From<$ty> for [$elem; $len] $($where_clause)* {
+            fn from(s: $ty) -> [$elem; $len] {
+                unsafe { $crate::__private::transmute(s) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::From<[$elem; $len]> for $ty $($where_clause)* {
+            fn from(array: [$elem; $len]) -> $ty {
+                unsafe { $crate::__private::transmute(array) }
+            }
+        }
+
+        impl $($impl_generics_a)* ::std::convert::From<&$a $ty> for &$a [$elem; $len]
+            $($where_clause)*
+        {
+            fn from(s: &$a $ty) -> &$a [$elem; $len] {
+                unsafe { $crate::__private::transmute_ref(s) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::AsRef<[$elem; $len]> for $ty $($where_clause)* {
+            fn as_ref(&self) -> &[$elem; $len] {
+                unsafe { $crate::__private::transmute_ref(self) }
+            }
+        }
+
+        impl $($impl_generics_a)* ::std::convert::From<&$a [$elem; $len]> for &$a $ty
+            $($where_clause)*
+        {
+            fn from(array: &$a [$elem; $len]) -> &$a $ty {
+                unsafe { $crate::__private::transmute_ref(array) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::AsRef<$ty> for [$elem; $len] $($where_clause)* {
+            fn as_ref(&self) -> &$ty {
+                unsafe { $crate::__private::transmute_ref(self) }
+            }
+        }
+
+        impl $($impl_generics_a)* ::std::convert::From<&$a mut $ty> for &$a mut [$elem; $len]
+            $($where_clause)*
+        {
+            fn from(s: &$a mut $ty) -> &$a mut [$elem; $len] {
+                unsafe { $crate::__private::transmute_mut(s) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::AsMut<[$elem; $len]> for $ty $($where_clause)* {
+            fn as_mut(&mut self) -> &mut [$elem; $len] {
+                unsafe { $crate::__private::transmute_mut(self) }
+            }
+        }
+
+        impl $($impl_generics_a)* ::std::convert::From<&$a mut [$elem; $len]> for &$a mut $ty
+            $($where_clause)*
+        {
+            fn from(array: &$a mut [$elem; $len]) -> &$a mut $ty {
+                unsafe { $crate::__private::transmute_mut(array) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::AsMut<$ty> for [$elem; $len] $($where_clause)* {
+            fn as_mut(&mut self) -> &mut $ty {
+                unsafe { $crate::__private::transmute_mut(self) }
+            }
+        }
+
+        impl $($impl_generics_a)* ::std::convert::From<&$a $ty> for &$a [$elem] $($where_clause)* {
+            fn from(s: &$a $ty) -> &$a [$elem] {
+                unsafe { $crate::__private::as_slice::<_, _, { $len }>(s) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::AsRef<[$elem]> for $ty $($where_clause)* {
+            fn as_ref(&self) -> &[$elem] {
+                unsafe { $crate::__private::as_slice::<_, _, { $len }>(self) }
+            }
+        }
+
+        impl $($impl_generics_a)* ::std::convert::From<&$a [$elem]> for &$a $ty $($where_clause)* {
+            #[doc = $from_slice_doc]
+            fn from(slice: &$a [$elem]) -> &$a $ty {
+                unsafe { $crate::__private::from_slice::<_, _, { $len }>(slice) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::AsRef<$ty> for [$elem] $($where_clause)* {
+            #[doc = $from_slice_doc]
+            fn as_ref(&self) -> &$ty {
+                unsafe { $crate::__private::from_slice::<_, _, { $len }>(self) }
+            }
+        }
+
+        impl $($impl_generics_a)* ::std::convert::From<&$a mut $ty> for &$a mut [$elem]
+            $($where_clause)*
+        {
+            fn from(s: &$a mut $ty) -> &$a mut [$elem] {
+                unsafe { $crate::__private::as_mut_slice::<_, _, { $len }>(s) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::AsMut<[$elem]> for $ty $($where_clause)* {
+            fn as_mut(&mut self) -> &mut [$elem] {
+                unsafe { $crate::__private::as_mut_slice::<_, _, { $len }>(self) }
+            }
+        }
+
+        impl $($impl_generics_a)* ::std::convert::From<&$a mut [$elem]> for &$a mut $ty
+            $($where_clause)*
+        {
+            #[doc = $from_slice_doc]
+            fn from(slice: &$a mut [$elem]) -> &$a mut $ty {
+                unsafe { $crate::__private::from_slice_mut::<_, _, { $len }>(slice) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::AsMut<$ty> for [$elem] $($where_clause)* {
+            #[doc = $from_slice_doc]
+            fn as_mut(&mut self) -> &mut $ty {
+                unsafe { $crate::__private::from_slice_mut::<_, _, { $len }>(self) }
+            }
+        }
+    };
+}
+
+/// Implements the conversions to/from flat arrays and slices of scalars for
+/// structs with the `flatten` option.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_flat_struct_array_convert {
+    ([$($impl_generics:tt)*] [$($impl_generics_a:tt)*] $a:lifetime, $ty:ty,
+     [$scalar:ty; $flat_len:expr], [$($where_clause:tt)*]) => {
+        impl $($impl_generics)* ::std::convert::From<$ty> for [$scalar; $flat_len]
+            $($where_clause)*
+        {
+            fn from(s: $ty) -> [$scalar; $flat_len] {
+                unsafe { $crate::__private::transmute(s) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::From<[$scalar; $flat_len]> for $ty
+            $($where_clause)*
+        {
+            fn from(array: [$scalar; $flat_len]) -> $ty {
+                unsafe { $crate::__private::transmute(array) }
+            }
+        }
+
+        impl $($impl_generics_a)* ::std::convert::From<&$a $ty> for &$a [$scalar; $flat_len]
+            $($where_clause)*
+        {
+            fn from(s: &$a $ty) -> &$a [$scalar; $flat_len] {
+                unsafe { $crate::__private::transmute_ref(s) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::AsRef<[$scalar; $flat_len]> for $ty
+            $($where_clause)*
+        {
+            fn as_ref(&self) -> &[$scalar; $flat_len] {
+                unsafe { $crate::__private::transmute_ref(self) }
+            }
+        }
+
+        impl $($impl_generics_a)* ::std::convert::From<&$a mut $ty>
+            for &$a mut [$scalar; $flat_len] $($where_clause)*
+        {
+            fn from(s: &$a mut $ty) -> &$a mut [$scalar; $flat_len] {
+                unsafe { $crate::__private::transmute_mut(s) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::AsMut<[$scalar; $flat_len]> for $ty
+            $($where_clause)*
+        {
+            fn as_mut(&mut self) -> &mut [$scalar; $flat_len] {
+                unsafe { $crate::__private::transmute_mut(self) }
+            }
+        }
+
+        impl $($impl_generics_a)* ::std::convert::From<&$a $ty> for &$a [$scalar]
+            $($where_clause)*
+        {
+            fn from(s: &$a $ty) -> &$a [$scalar] {
+                unsafe { $crate::__private::as_slice::<_, _, { $flat_len }>(s) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::AsRef<[$scalar]> for $ty $($where_clause)* {
+            fn as_ref(&self) -> &[$scalar] {
+                unsafe { $crate::__private::as_slice::<_, _, { $flat_len }>(self) }
+            }
+        }
+
+        impl $($impl_generics_a)* ::std::convert::From<&$a mut $ty> for &$a mut [$scalar]
+            $($where_clause)*
+        {
+            fn from(s: &$a mut $ty) -> &$a mut [$scalar] {
+                unsafe { $crate::__private::as_mut_slice::<_, _, { $flat_len }>(s) }
+            }
+        }
+
+        impl $($impl_generics)* ::std::convert::AsMut<[$scalar]> for $ty $($where_clause)* {
+            fn as_mut(&mut self) -> &mut [$scalar] {
+                unsafe { $crate::__private::as_mut_slice::<_, _, { $flat_len }>(self) }
+            }
+        }
+    };
+}
@@ -0,0 +1,160 @@
+//! Implementation of `Serialize` and `Deserialize` for the
+//! `#[struct_array(serde)]` option.
+//!
+//! The generated implementations delegate to the functions in this module,
+//! which are generic over the struct array.
+
+pub use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use serde::de::{self, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeStruct, SerializeTuple};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr;
+
+use StructArray;
+
+/// Moves the elements of a vector of length `S::LEN` into a struct.
+fn from_vec<S: StructArray>(mut elems: Vec<S::Elem>) -> S {
+    let () = S::__ASSERT_LAYOUT;
+    assert_eq!(elems.len(), S::LEN);
+    let array = unsafe {
+        let array = ptr::read(super::cast::<S, _, S::Array>(elems.as_ptr()));
+        elems.set_len(0);
+        array
+    };
+    S::from_array(array)
+}
+
+/// Serializes the struct as a tuple of its elements.
+pub fn serialize_seq<S, Ser>(s: &S, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+where
+    S: StructArray,
+    S::Elem: Serialize,
+    Ser: Serializer,
+{
+    let mut tuple = serializer.serialize_tuple(S::LEN)?;
+    for elem in s.as_slice() {
+        tuple.serialize_element(elem)?;
+    }
+    tuple.end()
+}
+
+/// Serializes the struct as a struct with the given field names, one for each
+/// element.
+pub fn serialize_map<S, Ser>(s: &S,
+                             name: &'static str,
+                             fields: &'static [&'static str],
+                             serializer: Ser)
+                             -> Result<Ser::Ok, Ser::Error>
+where
+    S: StructArray,
+    S::Elem: Serialize,
+    Ser: Serializer,
+{
+    let mut state = serializer.serialize_struct(name, S::LEN)?;
+    for (field, elem) in fields.iter().zip(s.as_slice()) {
+        state.serialize_field(field, elem)?;
+    }
+    state.end()
+}
+
+/// Visitor that reads the elements from a sequence of length `S::LEN`.
+struct SeqVisitor<S>(PhantomData<S>);
+
+impl<'de, S> Visitor<'de> for SeqVisitor<S>
+where
+    S: StructArray,
+    S::Elem: Deserialize<'de>,
+{
+    type Value = S;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an array of length {}", S::LEN)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<S, A::Error> {
+        let mut elems = Vec::with_capacity(S::LEN);
+        while elems.len() < S::LEN {
+            match seq.next_element()? {
+                Some(elem) => elems.push(elem),
+                None => return Err(de::Error::invalid_length(elems.len(), &self)),
+            }
+        }
+        if seq.next_element::<IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(S::LEN + 1, &self));
+        }
+        Ok(from_vec(elems))
+    }
+}
+
+/// Deserializes the struct from a tuple of its elements.
+pub fn deserialize_seq<'de, S, D>(deserializer: D) -> Result<S, D::Error>
+where
+    S: StructArray,
+    S::Elem: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_tuple(S::LEN, SeqVisitor(PhantomData))
+}
+
+/// Visitor that reads the elements from a map with the given field names, or
+/// from a sequence like `SeqVisitor`.
+struct MapVisitor<S> {
+    name: &'static str,
+    fields: &'static [&'static str],
+    marker: PhantomData<S>,
+}
+
+impl<'de, S> Visitor<'de> for MapVisitor<S>
+where
+    S: StructArray,
+    S::Elem: Deserialize<'de>,
+{
+    type Value = S;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "struct {}", self.name)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<S, A::Error> {
+        SeqVisitor(PhantomData).visit_seq(seq)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<S, A::Error> {
+        let mut elems: Vec<Option<S::Elem>> = (0..S::LEN).map(|_| None).collect();
+        while let Some(key) = map.next_key::<String>()? {
+            // Unknown keys are skipped, as by `#[derive(Deserialize)]`.
+            let index = match self.fields.iter().position(|field| *field == key) {
+                Some(index) => index,
+                None => {
+                    map.next_value::<IgnoredAny>()?;
+                    continue;
+                }
+            };
+            if elems[index].is_some() {
+                return Err(de::Error::duplicate_field(self.fields[index]));
+            }
+            elems[index] = Some(map.next_value()?);
+        }
+        let elems = elems.into_iter()
+            .zip(self.fields)
+            .map(|(elem, field)| elem.ok_or_else(|| de::Error::missing_field(field)))
+            .collect::<Result<_, _>>()?;
+        Ok(from_vec(elems))
+    }
+}
+
+/// Deserializes the struct from a struct with the given field names, one for
+/// each element.
+pub fn deserialize_map<'de, S, D>(deserializer: D,
+                                  name: &'static str,
+                                  fields: &'static [&'static str])
+                                  -> Result<S, D::Error>
+where
+    S: StructArray,
+    S::Elem: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_struct(name, fields, MapVisitor { name, fields, marker: PhantomData })
+}
@@ -9,14 +9,18 @@
 //! following properties:
 //!
 //!   * all the fields must be public (because they are exposed in
-//!     arrays/slices created by the conversion functions)
-//!   * all the fields must have the same type
-//!   * the struct must have at least one field
+//!     arrays/slices created by the conversion functions), except for
+//!     zero-sized fields excluded from the array
+//!   * all the fields must have the same type `T`, or be arrays `[T; K]` of
+//!     that type, in which case the elements of the arrays are elements of the
+//!     struct array
+//!   * the struct must have at least one field, unless the element type is
+//!     given with the `#[struct_array(elem = "T")]` attribute
 //!   * the struct must have the `#[repr(C)]` attribute
 //!
 //! # Example
 //!
-//! ```ignore
+//! ```
 //! #[macro_use]
 //! extern crate struct_array;
 //!
@@ -77,7 +81,12 @@
 //! # Trait implementations
 //!
 //! Deriving `StructArray` for a struct causes it to implement all the methods
-//! provided by `StructArrayDeref` and `StructArrayConvert`.
+//! provided by `StructArrayDeref` and `StructArrayConvert`. It also implements
+//! the [`StructArray`](trait.StructArray.html) trait for the struct, along with
+//! the items enabled by the `#[struct_array(...)]` options described below.
+//! `StructArrayDeref` and `StructArrayConvert` implement only the standard
+//! library traits listed below, so they can be derived on their own or
+//! together.
 //!
 //! Deriving `StructArrayDeref` for a struct `Foo` causes it to implement:
 //!
@@ -108,350 +117,1138 @@
 //!
 //! Note that converting from a slice will panic if the `len()` of the slice
 //! does not must match the number of fields in the struct.
+//!
+//! Coherence rules out implementing these standard library traits once for all
+//! struct arrays, so the derives still generate the same 20 implementations for
+//! each struct as version 0.2.1 did, and `#[derive(StructArray)]` also
+//! implements the `StructArray` and `FlatStructArray` traits. The number of
+//! implementations cannot be reduced, so the compile time of crates using the
+//! derives is about the same as with version 0.2.1. `benches/compile_time.rs`
+//! compares the two on a crate with a few hundred structs.
+//!
+//! # Array fields and indices
+//!
+//! Fields of type `[T; K]` can be mixed with fields of the element type `T`;
+//! the struct array then has the total number of elements of all the fields.
+//! (If all the fields have the same array type, the arrays themselves are the
+//! elements.) For structs with named fields, the derives also generate an
+//! associated constant with the index of each field in the array, e.g.
+//! `Vertex::W_INDEX` for a field `w`, which is the index of the first element
+//! for array fields.
+//!
+//! # Element type
+//!
+//! The element type can be given explicitly with the `#[struct_array(elem =
+//! "T")]` attribute. This allows struct arrays with zero fields, whose array
+//! is `[T; 0]`, and chooses `T` rather than `[T; K]` as the element type of a
+//! struct whose fields all have the array type `[T; K]`. Struct arrays with
+//! zero fields or zero-sized elements support all the same conversions as
+//! other struct arrays.
+//!
+//! # Zero-sized fields
+//!
+//! Zero-sized fields, such as markers for units or coordinate frames, can be
+//! excluded from the array with the `#[struct_array(skip)]` attribute. Fields
+//! of type `PhantomData` are excluded automatically. Excluded fields do not
+//! need to be public, and converting an array into the struct fills them in
+//! with their `Default` values. Since references to arrays can also be viewed
+//! as references to the struct, which creates the excluded fields out of
+//! nothing, their types must implement the unsafe
+//! [`Marker`](trait.Marker.html) trait, and excluding a field of another type
+//! is a compile-time error:
+//!
+//! ```compile_fail
+//! #[macro_use]
+//! extern crate struct_array;
+//!
+//! /// Proof that the hardware was initialized.
+//! #[derive(Default)]
+//! pub struct Initialized(());
+//!
+//! #[derive(StructArray)]
+//! #[repr(C)]
+//! struct Registers {
+//!     pub status: u32,
+//!     pub control: u32,
+//!     #[struct_array(skip)]
+//!     initialized: Initialized,
+//! }
+//!
+//! fn main() {}
+//! ```
+//!
+//! # Nested struct arrays
+//!
+//! A struct array whose fields are themselves struct arrays can be given the
+//! `#[struct_array(flatten)]` attribute. In addition to the usual conversions
+//! with arrays of its fields, it is then also convertible to/from a flat array
+//! of the innermost fields (e.g. `[f32; 6]` for a struct with two `Vec3`
+//! fields), and viewable as a slice of them. See
+//! [`FlatStructArray`](trait.FlatStructArray.html) for details. The attribute
+//! is not supported on generic structs.
+//!
+//! # Swizzles
+//!
+//! The `#[struct_array(swizzle)]` attribute generates swizzle accessors, as is
+//! common in graphics code. On a struct with fields `x`, `y`, `z` and `w`, there
+//! is a getter for every combination of two to four fields, such as `xy()`,
+//! `zyx()` and `xxxx()`, which returns the values of the fields as an array, and
+//! a setter for every combination without repeated fields, such as
+//! `set_xy(value)`, which accepts anything convertible into such an array. The
+//! names of fields with more than one letter are joined with underscores (e.g.
+//! `end_start()`). Getters whose names would be keywords, such as `if()` on a
+//! struct with fields `i` and `f`, are not generated, but the setters are.
+//! Accessors whose names could be joined from different fields, such as
+//! `a_b_c()` on a struct with fields `a`, `b_c`, `a_b` and `c`, are not
+//! generated at all.
+//!
+//! The number of accessors grows quickly with the number of fields, so the
+//! maximum number of fields in a swizzle can be limited with
+//! `#[struct_array(swizzle(max_len = 2))]`. It defaults to four, and must be
+//! given for structs with more than four fields. Getters can also return another
+//! struct array instead of an array, e.g.
+//! `#[struct_array(swizzle(len2 = "Vec2", len3 = "Vec3"))]` makes `xy()` return
+//! a `Vec2`. The attribute requires named fields that are not arrays.
+//!
+//! # Sub-struct views
+//!
+//! A contiguous range of fields can be viewed as another struct array with the
+//! same element type, without copying, e.g.
+//! `#[struct_array(view(Vec3 = "x..=z"))]` on a `Vec4` generates
+//! `as_vec3(&self) -> &Vec3` and `as_vec3_mut(&mut self) -> &mut Vec3`. The
+//! range is written like a Rust range of field names (or indices, for tuple
+//! structs), such as `x..=z`, `y..w` or `..z`. Several views can be listed, but
+//! only one per target type. It is a compile-time error if the length of the
+//! target does not match the number of elements in the range.
+//!
+//! # Extending and truncating
+//!
+//! A struct array whose first fields correspond to a shorter struct array with
+//! the same element type can declare this with e.g.
+//! `#[struct_array(extend(Vec3 = "x..=z"))]` on a `Vec4`. This generates
+//! `Vec3::extend(self, w) -> Vec4`, `Vec4::truncate(self) -> Vec3`, and the
+//! conversions `From<(Vec3, f32)> for Vec4` and `From<Vec4> for (Vec3, f32)`.
+//! The remaining fields are passed as a single element if there is only one, or
+//! as an array otherwise. Since the methods have fixed names, a struct can extend
+//! only one other struct, and a struct can be extended by only one other struct.
+//! The shorter struct must be defined in the same crate, and the option is not
+//! supported on generic structs. It is a compile-time error if the length of the
+//! shorter struct does not match the number of elements in the range.
+//!
+//! # Reordering fields
+//!
+//! For struct arrays with the same fields in a different order, such as `Rgb` and
+//! `Bgr` pixels, `#[struct_array(convert_to(Bgr))]` on `Rgb` generates
+//! `From<Rgb> for Bgr`, which matches the fields by name. It is a compile-time
+//! error if a field is missing from either struct. The
+//! `StructArray::convert_slice_in_place` method then converts a whole
+//! `&mut [Rgb]` into a `&mut [Bgr]` without allocating. The option requires named
+//! fields and is not supported on generic structs.
+//!
+//! # Arithmetic operators
+//!
+//! The `#[struct_array(ops)]` attribute implements the `Add`, `Sub`, `Mul` and
+//! `Div` operators element-wise, e.g. `a + b` adds each element of `a` to the
+//! corresponding element of `b`. For generic structs, each operator is implemented
+//! when the element type implements it. Structs with their own arithmetic, such as
+//! vectors with a dot product, can omit the attribute and implement the operators
+//! themselves.
+//!
+//! # Copying mixed-type structs
+//!
+//! Structs whose fields have differing types, such as telemetry records with
+//! `u16`, `i32` and `f32` fields, can't be viewed as arrays, but
+//! `#[derive(StructArrayCopy)]` generates copying conversions for them instead.
+//! It doesn't require `#[repr(C)]` or public fields, and generates
+//! `to_array::<U>(&self) -> [U; N]`, which converts each field with `Into<U>`,
+//! and `from_array::<U>(array) -> Result<Self, FieldConversionError>`, which
+//! converts each value with `TryFrom<U>` and reports the first field whose value
+//! could not be converted. Fields excluded with `#[struct_array(skip)]` (or of
+//! type `PhantomData`) are not copied and are filled in with their `Default`
+//! values. This derive doesn't implement the `StructArray` trait.
+//!
+//! # Groups of fields
+//!
+//! A `#[repr(C)]` struct with fields of differing types can still contain runs of
+//! fields of the same type, such as the coordinates in a packet header. Marking
+//! each field of a run with `#[struct_array(group = "coords")]` and deriving
+//! `StructArrayGroups` generates `coords(&self) -> &[f32; 3]` and
+//! `coords_mut(&mut self) -> &mut [f32; 3]`, which view the fields in place.
+//! The fields of a group must be public, contiguous, and of the same type, and
+//! their offsets in the struct are checked at compile time. The struct must not
+//! also be `#[repr(packed)]`, since the fields of a group would then not be
+//! aligned.
+//!
+//! ```compile_fail
+//! #[macro_use]
+//! extern crate struct_array;
+//!
+//! #[derive(StructArrayGroups)]
+//! #[repr(C)]
+//! #[repr(packed)]
+//! struct Header {
+//!     pub tag: u8,
+//!     #[struct_array(group = "coords")]
+//!     pub x: f32,
+//!     #[struct_array(group = "coords")]
+//!     pub y: f32,
+//! }
+//!
+//! fn main() {}
+//! ```
+//!
+//! # Serde
+//!
+//! With the `serde` feature of this crate enabled, the `#[struct_array(serde)]`
+//! attribute generates `Serialize` and `Deserialize` implementations that encode
+//! the struct array as a tuple of its elements, e.g. `[1.0, 2.0, 3.0]` in JSON.
+//! Deserializing a sequence of the wrong length fails with an error like
+//! `invalid length 2, expected an array of length 3`. With
+//! `#[struct_array(serde = "map")]`, the struct is encoded with its field names
+//! instead, like `#[derive(Serialize, Deserialize)]` would, which requires named
+//! fields that are not arrays. (`serde = "seq"` is the same as just `serde`.)
+//! Fields excluded from the array are not serialized. As with the derive, unknown
+//! fields are ignored when deserializing.
+//!
+//! # Binary I/O and byte order
+//!
+//! For struct arrays of primitive numbers (the integer types of a fixed size,
+//! `f32` and `f64`, which implement the `Primitive` trait), the `StructArray`
+//! trait provides `read_le`/`read_be` and `write_le`/`write_be` to read and write
+//! a struct as packed little- or big-endian elements, and
+//! `read_slice_into_le`/`read_slice_into_be` and
+//! `write_slice_le`/`write_slice_be` for slices of structs. The slice methods
+//! copy the bytes of the flat slice of elements directly, converting the byte
+//! order only if it differs from the host's. Reading past the end of the input
+//! returns an `io::Error` of kind `UnexpectedEof`.
+//!
+//! For conversions in memory, such as decoding network buffers in place, the
+//! trait also provides `to_le`, `to_be`, `from_le`, `from_be` and `swap_bytes`,
+//! which convert each element of a struct, and `to_le_slice`, `to_be_slice`,
+//! `from_le_slice`, `from_be_slice` and `swap_bytes_slice`, which convert a slice
+//! of structs in place through its flat slice of elements.
+//!
+//! Without copying, `as_ne_bytes` views a struct as its bytes in the native byte
+//! order, and `try_from_bytes` and `try_slice_from_bytes` view bytes as a struct
+//! or a slice of structs. These return a `ByteViewError` if the length of the
+//! bytes doesn't match the size of the struct (or a multiple of it), or if the
+//! bytes aren't aligned for the element type.
+//!
+//! # bytemuck
+//!
+//! With the `bytemuck` feature of this crate enabled, the
+//! `#[struct_array(bytemuck)]` attribute implements bytemuck's `Zeroable` and
+//! `Pod` traits for the struct, so that e.g. `bytemuck::cast_slice` works on
+//! slices of it. Every field, including excluded zero-sized fields, must be
+//! `Pod`, and the struct must be `Copy`. The absence of padding is checked at
+//! compile time. The option is not supported on generic structs.
+//!
+//! # zerocopy
+//!
+//! This crate does not implement zerocopy's traits. zerocopy's own derives work
+//! on struct arrays, and the byte views of `StructArray`, such as `as_ne_bytes`,
+//! do not clash with zerocopy's methods when both traits are in scope.
+//!
+//! # mint
+//!
+//! With the `mint` feature of this crate enabled, the
+//! `#[struct_array(mint(Vector3, Point3))]` attribute implements `From` in both
+//! directions between the struct and each of the listed `mint` types, which can be
+//! `Vector2`, `Vector3`, `Vector4`, `Point2` and `Point3`, with the element type as
+//! the type parameter. This lets the struct be passed to any math library that
+//! accepts `mint` types. The number of elements must match the `mint` type, which
+//! is checked at compile time. The option is not supported on generic structs.
+//!
+//! # nalgebra
+//!
+//! With the `nalgebra` feature of this crate enabled, the
+//! `#[struct_array(nalgebra)]` attribute implements `From` in both directions
+//! between the struct and nalgebra's `SVector<T, N>` and `Point<T, N>`, where `T`
+//! is the element type and `N` the number of elements. It also generates
+//! `as_vector_view(&self)` and `as_vector_view_mut(&mut self)`, which view the
+//! struct in place as a column vector (`SVectorView` or `SVectorViewMut`), and
+//! `as_matrix_view(slice)` and `as_matrix_view_mut(slice)`, which view a slice of
+//! structs as a `DMatrixView` or `DMatrixViewMut` with `N` rows and a column for
+//! each struct. The element type must be a nalgebra `Scalar`. The option is not
+//! supported on generic structs.
+//!
+//! # ndarray
+//!
+//! With the `ndarray` feature of this crate enabled, the `StructArray` trait
+//! provides zero-copy `ndarray` views. `as_ndarray_view` and `as_ndarray_view_mut`
+//! view a struct as a one-dimensional array of its fields, and
+//! `slice_as_ndarray_view` and `slice_as_ndarray_view_mut` view a slice of `N`
+//! structs as a two-dimensional array of shape `(N, LEN)`, with a row for each
+//! struct. In reverse, `slice_from_ndarray_view` and `slice_from_ndarray_view_mut`
+//! view a two-dimensional array as a slice of structs, returning a `ShapeError` if
+//! the number of columns isn't the length of the struct or if the array isn't
+//! contiguous in standard (row-major) order.
+//!
+//! # glam
+//!
+//! With the `glam` feature of this crate enabled, the `#[struct_array(glam)]`
+//! attribute implements `From` in both directions between the struct and the
+//! `glam` vector types with the same element type and number of elements, such as
+//! `Vec2`, `Vec3` and `Vec3A` for 2 or 3 `f32` elements, `DVec4` for 4 `f64`
+//! elements, `IVec2` for 2 `i32` elements and `UVec3` for 3 `u32` elements. Other
+//! element types and lengths are an error. The conversions copy the elements, so
+//! they also work with types of a different alignment and size, such as `Vec3A`.
+//!
+//! # approx
+//!
+//! With the `approx` feature of this crate enabled, the `#[struct_array(approx)]`
+//! attribute implements approx's `AbsDiffEq`, `RelativeEq` and `UlpsEq` traits for
+//! the struct, comparing the elements pairwise with the same tolerances as the
+//! element type, so that e.g. `assert_relative_eq!(a, b)` works on struct arrays
+//! directly. The struct must also implement `PartialEq`. For generic structs, the
+//! traits are implemented when the element type implements them.
+//!
+//! # num-traits
+//!
+//! With the `num-traits` feature of this crate enabled, the
+//! `#[struct_array(num_traits)]` attribute implements num-traits' `Bounded` trait
+//! with every element set to the corresponding value of the element type. Together
+//! with `#[struct_array(ops)]`, whose `Add` and `Mul` they require, it also
+//! implements `Zero` and `One` in the same way, so that the struct can be used in
+//! generic numeric code. (A struct with its own operators can implement `Zero` and
+//! `One` with `StructArray::from_array`.) For generic structs, each implementation
+//! requires the corresponding trait of the element type. If the struct's only type
+//! parameter is its element type, e.g. `Vec3<T>`, the attribute also generates
+//! `num_cast::<U>()`, which converts each element with `NumCast` and returns `None`
+//! if any element can't be represented in `U`. (It is named `num_cast` to avoid
+//! confusion with `StructArray::cast`, which reinterprets a struct array without
+//! converting its elements.)
+//!
+//! # Generic code
+//!
+//! The [`StructArray`](trait.StructArray.html) trait describes every struct
+//! array by its element type and length, so generic code can accept any of
+//! them:
+//!
+//! ```
+//! #[macro_use]
+//! extern crate struct_array;
+//!
+//! use struct_array::StructArray;
+//!
+//! #[derive(StructArray)]
+//! #[repr(C)]
+//! struct Vec2 {
+//!     pub x: f32,
+//!     pub y: f32,
+//! }
+//!
+//! fn sum<S: StructArray<Elem = f32>>(s: &S) -> f32 {
+//!     s.iter().sum()
+//! }
+//!
+//! fn main() {
+//!     assert_eq!(sum(&Vec2 { x: 1., y: 2. }), 3.);
+//! }
+//! ```
+//!
+//! The trait also provides `cast`, `cast_ref`, `cast_mut` and `cast_slice` to
+//! reinterpret a struct array as another one with the same element type and
+//! length, e.g. a `Vec3` as an `Rgb`.
 
-#![recursion_limit = "500"]
+extern crate struct_array_derive;
 
-extern crate proc_macro;
-use proc_macro::TokenStream;
+#[cfg(feature = "approx")]
+extern crate approx;
+#[cfg(feature = "bytemuck")]
+extern crate bytemuck;
+#[cfg(feature = "glam")]
+extern crate glam;
+#[cfg(feature = "mint")]
+extern crate mint;
+#[cfg(feature = "nalgebra")]
+extern crate nalgebra;
+#[cfg(feature = "ndarray")]
+extern crate ndarray;
+#[cfg(feature = "num-traits")]
+extern crate num_traits;
+#[cfg(feature = "serde")]
+extern crate serde;
 
-extern crate syn;
+pub use struct_array_derive::{StructArray, StructArrayConvert, StructArrayCopy, StructArrayDeref,
+                              StructArrayGroups};
 
-#[macro_use]
-extern crate quote;
-use quote::ToTokens;
+use std::error::Error;
+use std::fmt;
+use std::marker::{PhantomData, PhantomPinned};
+use std::mem::{self, ManuallyDrop};
+use std::{io, ptr, slice};
 
-/// Errors in the input to one of the macros.
-#[derive(Clone,Debug,Eq,PartialEq)]
-enum MacroInputError {
-    ZeroFields,
-    NonpublicField,
-    DifferingFieldTypes,
-    NotStruct,
-    NotReprC,
-}
+#[cfg(feature = "ndarray")]
+use ndarray::{ArrayView1, ArrayView2, ArrayViewMut1, ArrayViewMut2, ErrorKind, ShapeError};
 
-impl std::fmt::Display for MacroInputError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match *self {
-            MacroInputError::ZeroFields => write!(f, "the struct must have at least one field"),
-            MacroInputError::NonpublicField => write!(f, "all fields in the struct must be public"),
-            MacroInputError::DifferingFieldTypes => write!(f, "all fields in the struct must have the same type"),
-            MacroInputError::NotStruct => write!(f, "the type must be a struct (or tuple struct), not an enum"),
-            MacroInputError::NotReprC => write!(f, "the struct must have the #[repr(C)] attribute"),
-        }
+#[doc(hidden)]
+pub mod __private;
+mod primitive;
+
+pub use primitive::Primitive;
+
+/// A struct that can be viewed as an array of its fields.
+///
+/// This trait is implemented by each of the derive macros in this crate. The
+/// implementation only specifies the associated items; all the conversions
+/// are provided methods, and the standard library trait implementations
+/// generated by the derives delegate to them.
+///
+/// # Safety
+///
+/// `Self` must have the same size as `Self::Array`, which must be
+/// `[Self::Elem; Self::LEN]`, and the same alignment unless it is
+/// zero-sized, and the value of every field must be valid at the
+/// corresponding index of the array.
+pub unsafe trait StructArray: Sized {
+    /// Type of the fields.
+    type Elem;
+
+    /// Array with the same layout as the struct, `[Self::Elem; Self::LEN]`.
+    type Array: AsRef<[Self::Elem]> + AsMut<[Self::Elem]>;
+
+    /// Number of fields.
+    const LEN: usize;
+
+    /// Checks the layout at compile time. This is evaluated by the provided
+    /// methods when they are used, since it cannot be checked when the trait
+    /// is implemented for a generic struct.
+    #[doc(hidden)]
+    const __ASSERT_LAYOUT: () = __private::assert_layout::<Self>();
+
+    /// Converts the struct into an array.
+    fn into_array(self) -> Self::Array {
+        let () = Self::__ASSERT_LAYOUT;
+        let s = ManuallyDrop::new(self);
+        unsafe { ptr::read(__private::cast::<Self, _, Self::Array>(&*s)) }
     }
-}
 
-impl std::error::Error for MacroInputError {
-    fn description(&self) -> &str {
-        match *self {
-            MacroInputError::ZeroFields => "struct had no fields",
-            MacroInputError::NonpublicField => "struct had at least one nonpublic field",
-            MacroInputError::DifferingFieldTypes => "struct had fields of differing types",
-            MacroInputError::NotStruct => "input was not a struct",
-            MacroInputError::NotReprC => "struct was missing the #[repr(C)] attribute",
-        }
+    /// Converts an array into the struct.
+    fn from_array(array: Self::Array) -> Self {
+        let () = Self::__ASSERT_LAYOUT;
+        let array = ManuallyDrop::new(array);
+        unsafe { ptr::read(__private::cast::<Self, _, Self>(&*array)) }
     }
 
-    fn cause(&self) -> Option<&std::error::Error> {
-        None
+    /// Returns a reference to the struct as an array.
+    fn as_array(&self) -> &Self::Array {
+        let () = Self::__ASSERT_LAYOUT;
+        unsafe { &*__private::cast::<Self, _, Self::Array>(self) }
     }
-}
 
-/// Relevant information about the struct from the macro input.
-struct StructInfo<'a> {
-    name: &'a syn::Ident,
-    generics: &'a syn::Generics,
-    field_type: &'a syn::Ty,
-    field_count: usize,
-}
+    /// Returns a mutable reference to the struct as an array.
+    fn as_array_mut(&mut self) -> &mut Self::Array {
+        let () = Self::__ASSERT_LAYOUT;
+        unsafe { &mut *__private::cast_mut::<Self, _, Self::Array>(self) }
+    }
 
-/// Extracts the relevant information from the macro input and checks that the
-/// struct meets the requirements for the macros.
-fn parse_input<'a>(ast: &'a syn::MacroInput) -> Result<StructInfo<'a>, MacroInputError> {
-    let repr_c =
-        syn::MetaItem::List("repr".into(),
-                            vec![syn::NestedMetaItem::MetaItem(syn::MetaItem::Word("C".into()))]);
-    if !ast.attrs.iter().any(|attr| attr.value == repr_c) {
-        Err(MacroInputError::NotReprC)
-    } else {
-        match ast.body {
-            syn::Body::Enum(_) => Err(MacroInputError::NotStruct),
-            syn::Body::Struct(ref data) => {
-                let field_type = &data.fields().first().ok_or(MacroInputError::ZeroFields)?.ty;
-                if data.fields().iter().any(|field| field.vis != syn::Visibility::Public) {
-                    Err(MacroInputError::NonpublicField)
-                } else if data.fields().iter().any(|field| field.ty != *field_type) {
-                    Err(MacroInputError::DifferingFieldTypes)
-                } else {
-                    Ok(StructInfo {
-                        name: &ast.ident,
-                        generics: &ast.generics,
-                        field_type: field_type,
-                        field_count: data.fields().len(),
-                    })
-                }
-            }
-        }
+    /// Views a reference to an array as a reference to the struct.
+    fn from_array_ref(array: &Self::Array) -> &Self {
+        let () = Self::__ASSERT_LAYOUT;
+        unsafe { &*__private::cast::<Self, _, Self>(array) }
     }
-}
 
-/// Implements derive of `StructArray`.
-///
-/// This function is called by the Rust compiler when compiling code that uses
-/// `#[derive(StructArray)]`.
-#[proc_macro_derive(StructArray)]
-pub fn derive_struct_array(input: TokenStream) -> TokenStream {
-    let source = input.to_string();
-
-    // Parse the string representation into a syntax tree.
-    let ast = syn::parse_macro_input(&source).unwrap();
-
-    // Check the struct and get the necessary info.
-    let struct_info = parse_input(&ast).unwrap_or_else(|err| {
-        panic!(format!("Error expanding #[derive(StructArray)]: {}", err))
-    });
-
-    // Build the output.
-    let mut expanded = quote::Tokens::new();
-    impl_struct_array_deref(&struct_info).to_tokens(&mut expanded);
-    impl_struct_array_convert(&struct_info).to_tokens(&mut expanded);
-
-    // Return the generated impl as a TokenStream.
-    expanded.parse().unwrap()
-}
+    /// Views a mutable reference to an array as a mutable reference to the
+    /// struct.
+    fn from_array_mut(array: &mut Self::Array) -> &mut Self {
+        let () = Self::__ASSERT_LAYOUT;
+        unsafe { &mut *__private::cast_mut::<Self, _, Self>(array) }
+    }
 
-/// Implements derive of `StructArrayDeref`.
-///
-/// This function is called by the Rust compiler when compiling code that uses
-/// `#[derive(StructArrayDeref)]`.
-#[proc_macro_derive(StructArrayDeref)]
-pub fn derive_struct_array_deref(input: TokenStream) -> TokenStream {
-    let source = input.to_string();
+    /// Returns a slice of the fields.
+    fn as_slice(&self) -> &[Self::Elem] {
+        let () = Self::__ASSERT_LAYOUT;
+        unsafe { slice::from_raw_parts(__private::cast::<Self, _, Self::Elem>(self), Self::LEN) }
+    }
 
-    // Parse the string representation into a syntax tree.
-    let ast = syn::parse_macro_input(&source).unwrap();
+    /// Returns a mutable slice of the fields.
+    fn as_mut_slice(&mut self) -> &mut [Self::Elem] {
+        let () = Self::__ASSERT_LAYOUT;
+        unsafe { slice::from_raw_parts_mut(__private::cast_mut::<Self, _, Self::Elem>(self), Self::LEN) }
+    }
 
-    // Check the struct and get the necessary info.
-    let struct_info = parse_input(&ast).unwrap_or_else(|err| {
-        panic!(format!("Error expanding #[derive(StructArrayDeref)]: {}", err))
-    });
+    /// Views a slice of fields as a reference to the struct.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `len()` of the slice is not `Self::LEN`.
+    fn from_slice(slice: &[Self::Elem]) -> &Self {
+        let () = Self::__ASSERT_LAYOUT;
+        assert_eq!(slice.len(), Self::LEN);
+        unsafe { &*__private::cast::<Self, _, Self>(slice.as_ptr()) }
+    }
 
-    // Build the output.
-    let expanded = impl_struct_array_deref(&struct_info);
+    /// Views a mutable slice of fields as a mutable reference to the struct.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `len()` of the slice is not `Self::LEN`.
+    fn from_slice_mut(slice: &mut [Self::Elem]) -> &mut Self {
+        let () = Self::__ASSERT_LAYOUT;
+        assert_eq!(slice.len(), Self::LEN);
+        unsafe { &mut *__private::cast_mut::<Self, _, Self>(slice.as_mut_ptr()) }
+    }
 
-    // Return the generated impl as a TokenStream.
-    expanded.parse().unwrap()
-}
+    /// Returns an iterator over the fields.
+    fn iter(&self) -> slice::Iter<'_, Self::Elem> {
+        self.as_slice().iter()
+    }
 
-fn impl_struct_array_deref(struct_info: &StructInfo) -> quote::Tokens {
-    let StructInfo { name, generics, field_type, field_count } = *struct_info;
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-    quote! {
-        impl #impl_generics ::std::ops::Deref for #name #ty_generics #where_clause {
-            type Target = [#field_type; #field_count];
-
-            fn deref(&self) -> &[#field_type; #field_count] {
-                unsafe {
-                    &*(self as *const #name as *const [#field_type; #field_count])
-                }
-            }
+    /// Returns an iterator that allows modifying each field.
+    fn iter_mut(&mut self) -> slice::IterMut<'_, Self::Elem> {
+        self.as_mut_slice().iter_mut()
+    }
+
+    /// Replaces each field with the result of calling `f` on it.
+    fn map_in_place<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Self::Elem) -> Self::Elem,
+    {
+        for elem in self.as_mut_slice() {
+            *elem = f(elem);
         }
+    }
 
-        impl #impl_generics ::std::ops::DerefMut for #name #ty_generics #where_clause {
-            fn deref_mut(&mut self) -> &mut [#field_type; #field_count] {
-                unsafe {
-                    &mut *(self as *mut #name as *mut [#field_type; #field_count])
-                }
-            }
+    /// Views a slice of structs as a flat slice of their fields.
+    fn as_flat_slice(slice: &[Self]) -> &[Self::Elem] {
+        let () = Self::__ASSERT_LAYOUT;
+        unsafe {
+            slice::from_raw_parts(__private::cast::<Self, _, Self::Elem>(slice.as_ptr()),
+                                  slice.len() * Self::LEN)
         }
     }
-}
 
-/// Implements derive of `StructArrayConvert`.
-///
-/// This function is called by the Rust compiler when compiling code that uses
-/// `#[derive(StructArrayConvert)]`.
-#[proc_macro_derive(StructArrayConvert)]
-pub fn derive_struct_array_convert(input: TokenStream) -> TokenStream {
-    let source = input.to_string();
+    /// Views a mutable slice of structs as a flat mutable slice of their
+    /// fields.
+    fn as_flat_slice_mut(slice: &mut [Self]) -> &mut [Self::Elem] {
+        let () = Self::__ASSERT_LAYOUT;
+        unsafe {
+            slice::from_raw_parts_mut(__private::cast_mut::<Self, _, Self::Elem>(slice.as_mut_ptr()),
+                                      slice.len() * Self::LEN)
+        }
+    }
 
-    // Parse the string representation into a syntax tree.
-    let ast = syn::parse_macro_input(&source).unwrap();
+    /// Views a flat slice of fields as a slice of structs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `len()` of the slice is not a multiple of `Self::LEN`.
+    /// If `Self::LEN` is zero, the slice must be empty, and the result is an
+    /// empty slice.
+    fn from_flat_slice(slice: &[Self::Elem]) -> &[Self] {
+        let () = Self::__ASSERT_LAYOUT;
+        let len = __private::flat_to_struct_len::<Self>(slice.len());
+        unsafe { slice::from_raw_parts(__private::cast::<Self, _, Self>(slice.as_ptr()), len) }
+    }
 
-    // Check the struct and get the necessary info.
-    let struct_info = parse_input(&ast).unwrap_or_else(|err| {
-        panic!(format!("Error expanding #[derive(StructArrayConvert)]: {}", err))
-    });
+    /// Views a flat mutable slice of fields as a mutable slice of structs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `len()` of the slice is not a multiple of `Self::LEN`.
+    /// If `Self::LEN` is zero, the slice must be empty, and the result is an
+    /// empty slice.
+    fn from_flat_slice_mut(slice: &mut [Self::Elem]) -> &mut [Self] {
+        let () = Self::__ASSERT_LAYOUT;
+        let len = __private::flat_to_struct_len::<Self>(slice.len());
+        unsafe {
+            slice::from_raw_parts_mut(__private::cast_mut::<Self, _, Self>(slice.as_mut_ptr()), len)
+        }
+    }
 
-    // Build the output.
-    let expanded = impl_struct_array_convert(&struct_info);
+    /// Converts the struct into another struct array with the same element
+    /// type and length, e.g. a `Vec3` into an `Rgb`.
+    ///
+    /// It is a compile-time error if the lengths differ.
+    fn cast<T>(self) -> T
+    where
+        T: StructArray<Elem = Self::Elem>,
+    {
+        let () = __private::assert_same_shape::<Self, T>();
+        // Goes through the array, so that `T` fills in its own skipped fields.
+        let array = ManuallyDrop::new(self.into_array());
+        T::from_array(unsafe { ptr::read(__private::cast::<Self, _, T::Array>(&*array)) })
+    }
 
-    // Return the generated impl as a TokenStream.
-    expanded.parse().unwrap()
-}
+    /// Views a reference to the struct as a reference to another struct array
+    /// with the same element type and length.
+    ///
+    /// It is a compile-time error if the lengths differ.
+    fn cast_ref<T>(&self) -> &T
+    where
+        T: StructArray<Elem = Self::Elem>,
+    {
+        let () = __private::assert_same_shape::<Self, T>();
+        unsafe { &*__private::cast::<Self, _, T>(self) }
+    }
 
-fn impl_struct_array_convert(struct_info: &StructInfo) -> quote::Tokens {
-    let StructInfo { name, generics, field_type, field_count } = *struct_info;
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-    let from_slice_doc = format!("
-Performs the conversion.
-
-# Panics
-
-Panics if the `len()` of the slice is not {}.
-", field_count);
-    quote! {
-        impl #impl_generics From<#name> for [#field_type; #field_count] #ty_generics #where_clause {
-            fn from(s: #name) -> [#field_type; #field_count] {
-                unsafe {
-                    ::std::mem::transmute(s)
-                }
-            }
-        }
+    /// Views a mutable reference to the struct as a mutable reference to
+    /// another struct array with the same element type and length.
+    ///
+    /// It is a compile-time error if the lengths differ.
+    fn cast_mut<T>(&mut self) -> &mut T
+    where
+        T: StructArray<Elem = Self::Elem>,
+    {
+        let () = __private::assert_same_shape::<Self, T>();
+        unsafe { &mut *__private::cast_mut::<Self, _, T>(self) }
+    }
 
-        impl #impl_generics From<[#field_type; #field_count]> for #name #ty_generics #where_clause {
-            fn from(array: [#field_type; #field_count]) -> #name {
-                unsafe {
-                    ::std::mem::transmute(array)
-                }
-            }
-        }
+    /// Views a slice of structs as a slice of another struct array with the
+    /// same element type and length.
+    ///
+    /// It is a compile-time error if the lengths differ.
+    fn cast_slice<T>(slice: &[Self]) -> &[T]
+    where
+        T: StructArray<Elem = Self::Elem>,
+    {
+        let () = __private::assert_same_shape::<Self, T>();
+        unsafe { slice::from_raw_parts(__private::cast::<Self, _, T>(slice.as_ptr()), slice.len()) }
+    }
 
-        impl<'a> #impl_generics From<&'a #name> for &'a [#field_type; #field_count] #ty_generics #where_clause {
-            fn from(s: &'a #name) -> &'a [#field_type; #field_count] {
-                unsafe {
-                    &*(s as *const #name as *const [#field_type; #field_count])
-                }
-            }
+    /// Views a mutable slice of structs as a mutable slice of another struct
+    /// array with the same element type and length.
+    ///
+    /// It is a compile-time error if the lengths differ.
+    fn cast_slice_mut<T>(slice: &mut [Self]) -> &mut [T]
+    where
+        T: StructArray<Elem = Self::Elem>,
+    {
+        let () = __private::assert_same_shape::<Self, T>();
+        unsafe {
+            slice::from_raw_parts_mut(__private::cast_mut::<Self, _, T>(slice.as_mut_ptr()),
+                                      slice.len())
         }
+    }
 
-        impl #impl_generics ::std::convert::AsRef<[#field_type; #field_count]> for #name #ty_generics #where_clause {
-            fn as_ref(&self) -> &[#field_type; #field_count] {
-                unsafe {
-                    &*(self as *const #name as *const [#field_type; #field_count])
-                }
-            }
-        }
+    /// Reverses the byte order of each element.
+    fn swap_bytes(mut self) -> Self
+    where
+        Self::Elem: Primitive,
+    {
+        primitive::swap_bytes(self.as_mut_slice());
+        self
+    }
 
-        impl<'a> #impl_generics From<&'a [#field_type; #field_count]> for &'a #name #ty_generics #where_clause {
-            fn from(array: &[#field_type; #field_count]) -> &#name {
-                unsafe {
-                    &*(array as *const [#field_type; #field_count] as *const #name)
-                }
-            }
-        }
+    /// Converts each element from the native byte order to little endian.
+    ///
+    /// On little-endian hosts this is a no-op.
+    fn to_le(mut self) -> Self
+    where
+        Self::Elem: Primitive,
+    {
+        primitive::convert_endian(self.as_mut_slice(), true);
+        self
+    }
 
-        impl #impl_generics ::std::convert::AsRef<#name> for [#field_type; #field_count] #ty_generics #where_clause {
-            fn as_ref(&self) -> &#name {
-                unsafe {
-                    &*(self as *const [#field_type; #field_count] as *const #name)
-                }
-            }
-        }
+    /// Converts each element from the native byte order to big endian.
+    ///
+    /// On big-endian hosts this is a no-op.
+    fn to_be(mut self) -> Self
+    where
+        Self::Elem: Primitive,
+    {
+        primitive::convert_endian(self.as_mut_slice(), false);
+        self
+    }
 
-        impl<'a> #impl_generics From<&'a mut #name> for &'a mut [#field_type; #field_count] #ty_generics #where_clause {
-            fn from(s: &'a mut #name) -> &'a mut [#field_type; #field_count] {
-                unsafe {
-                    &mut *(s as *mut #name as *mut [#field_type; #field_count])
-                }
-            }
-        }
+    /// Converts each element from little endian to the native byte order.
+    ///
+    /// On little-endian hosts this is a no-op.
+    fn from_le(value: Self) -> Self
+    where
+        Self::Elem: Primitive,
+    {
+        value.to_le()
+    }
 
-        impl #impl_generics ::std::convert::AsMut<[#field_type; #field_count]> for #name #ty_generics #where_clause {
-            fn as_mut(&mut self) -> &mut [#field_type; #field_count] {
-                unsafe {
-                    &mut *(self as *mut #name as *mut [#field_type; #field_count])
-                }
-            }
-        }
+    /// Converts each element from big endian to the native byte order.
+    ///
+    /// On big-endian hosts this is a no-op.
+    fn from_be(value: Self) -> Self
+    where
+        Self::Elem: Primitive,
+    {
+        value.to_be()
+    }
 
-        impl<'a> #impl_generics From<&'a mut [#field_type; #field_count]> for &'a mut #name #ty_generics #where_clause {
-            fn from(array: &mut [#field_type; #field_count]) -> &mut #name {
-                unsafe {
-                    &mut *(array as *mut [#field_type; #field_count] as *mut #name)
-                }
-            }
-        }
+    /// Reverses the byte order of each element of each struct in the slice,
+    /// in place.
+    fn swap_bytes_slice(slice: &mut [Self])
+    where
+        Self::Elem: Primitive,
+    {
+        primitive::swap_bytes(Self::as_flat_slice_mut(slice));
+    }
 
-        impl #impl_generics ::std::convert::AsMut<#name> for [#field_type; #field_count] #ty_generics #where_clause {
-            fn as_mut(&mut self) -> &mut #name {
-                unsafe {
-                    &mut *(self as *mut [#field_type; #field_count] as *mut #name)
-                }
-            }
+    /// Converts each element of each struct in the slice from the native byte
+    /// order to little endian, in place.
+    fn to_le_slice(slice: &mut [Self])
+    where
+        Self::Elem: Primitive,
+    {
+        primitive::convert_endian(Self::as_flat_slice_mut(slice), true);
+    }
+
+    /// Converts each element of each struct in the slice from the native byte
+    /// order to big endian, in place.
+    fn to_be_slice(slice: &mut [Self])
+    where
+        Self::Elem: Primitive,
+    {
+        primitive::convert_endian(Self::as_flat_slice_mut(slice), false);
+    }
+
+    /// Converts each element of each struct in the slice from little endian
+    /// to the native byte order, in place.
+    fn from_le_slice(slice: &mut [Self])
+    where
+        Self::Elem: Primitive,
+    {
+        Self::to_le_slice(slice);
+    }
+
+    /// Converts each element of each struct in the slice from big endian to
+    /// the native byte order, in place.
+    fn from_be_slice(slice: &mut [Self])
+    where
+        Self::Elem: Primitive,
+    {
+        Self::to_be_slice(slice);
+    }
+
+    /// Reads the struct from packed little-endian elements.
+    ///
+    /// Returns an error of kind `UnexpectedEof` if the reader ends too soon.
+    fn read_le<R: io::Read>(reader: R) -> io::Result<Self>
+    where
+        Self::Elem: Primitive,
+    {
+        read_array::<Self, R>(reader, true)
+    }
+
+    /// Reads the struct from packed big-endian elements.
+    ///
+    /// Returns an error of kind `UnexpectedEof` if the reader ends too soon.
+    fn read_be<R: io::Read>(reader: R) -> io::Result<Self>
+    where
+        Self::Elem: Primitive,
+    {
+        read_array::<Self, R>(reader, false)
+    }
+
+    /// Writes the struct as packed little-endian elements.
+    fn write_le<W: io::Write>(&self, writer: W) -> io::Result<()>
+    where
+        Self::Elem: Primitive,
+    {
+        Self::write_slice_le(slice::from_ref(self), writer)
+    }
+
+    /// Writes the struct as packed big-endian elements.
+    fn write_be<W: io::Write>(&self, writer: W) -> io::Result<()>
+    where
+        Self::Elem: Primitive,
+    {
+        Self::write_slice_be(slice::from_ref(self), writer)
+    }
+
+    /// Fills the slice of structs with packed little-endian elements.
+    ///
+    /// The bytes are read directly into the slice, and the byte order of the
+    /// elements is converted afterwards if the host is big-endian. Returns an
+    /// error of kind `UnexpectedEof` if the reader ends too soon, in which
+    /// case the contents of the slice are unspecified.
+    fn read_slice_into_le<R: io::Read>(slice: &mut [Self], reader: R) -> io::Result<()>
+    where
+        Self::Elem: Primitive,
+    {
+        read_flat_slice(Self::as_flat_slice_mut(slice), reader, true)
+    }
+
+    /// Fills the slice of structs with packed big-endian elements.
+    ///
+    /// The bytes are read directly into the slice, and the byte order of the
+    /// elements is converted afterwards if the host is little-endian. Returns
+    /// an error of kind `UnexpectedEof` if the reader ends too soon, in which
+    /// case the contents of the slice are unspecified.
+    fn read_slice_into_be<R: io::Read>(slice: &mut [Self], reader: R) -> io::Result<()>
+    where
+        Self::Elem: Primitive,
+    {
+        read_flat_slice(Self::as_flat_slice_mut(slice), reader, false)
+    }
+
+    /// Writes the slice of structs as packed little-endian elements.
+    fn write_slice_le<W: io::Write>(slice: &[Self], writer: W) -> io::Result<()>
+    where
+        Self::Elem: Primitive,
+    {
+        write_flat_slice(Self::as_flat_slice(slice), writer, true)
+    }
+
+    /// Writes the slice of structs as packed big-endian elements.
+    fn write_slice_be<W: io::Write>(slice: &[Self], writer: W) -> io::Result<()>
+    where
+        Self::Elem: Primitive,
+    {
+        write_flat_slice(Self::as_flat_slice(slice), writer, false)
+    }
+
+    /// Returns the bytes of the elements, in the native byte order.
+    fn as_ne_bytes(&self) -> &[u8]
+    where
+        Self::Elem: Primitive,
+    {
+        primitive::as_bytes(self.as_slice())
+    }
+
+    /// Views bytes, in the native byte order, as a reference to the struct.
+    ///
+    /// Returns an error if the length of `bytes` is not the size of the
+    /// struct, or if `bytes` is not aligned for the element type.
+    fn try_from_bytes(bytes: &[u8]) -> Result<&Self, ByteViewError>
+    where
+        Self::Elem: Primitive,
+    {
+        if bytes.len() != mem::size_of::<Self>() {
+            return Err(ByteViewError::Length);
         }
+        let slice = Self::try_slice_from_bytes(bytes)?;
+        Ok(&slice[0])
+    }
 
-        impl<'a> #impl_generics From<&'a #name> for &'a [#field_type] #ty_generics #where_clause {
-            fn from(s: &'a #name) -> &'a [#field_type] {
-                unsafe {
-                    ::std::slice::from_raw_parts(s as *const #name as *const #field_type, #field_count)
-                }
-            }
+    /// Views bytes, in the native byte order, as a slice of structs.
+    ///
+    /// Returns an error if the length of `bytes` is not a multiple of the size
+    /// of the struct, or if `bytes` is not aligned for the element type.
+    fn try_slice_from_bytes(bytes: &[u8]) -> Result<&[Self], ByteViewError>
+    where
+        Self::Elem: Primitive,
+    {
+        let () = Self::__ASSERT_LAYOUT;
+        let size = mem::size_of::<Self>();
+        if size == 0 || !bytes.len().is_multiple_of(size) {
+            return Err(ByteViewError::Length);
+        }
+        if !bytes.as_ptr().cast::<Self>().is_aligned() {
+            return Err(ByteViewError::Alignment);
         }
+        // Every bit pattern is a valid value for an array of primitives.
+        unsafe { Ok(slice::from_raw_parts(bytes.as_ptr().cast::<Self>(), bytes.len() / size)) }
+    }
 
-        impl #impl_generics ::std::convert::AsRef<[#field_type]> for #name #ty_generics #where_clause {
-            fn as_ref(&self) -> &[#field_type] {
-                unsafe {
-                    ::std::slice::from_raw_parts(self as *const #name as *const #field_type, #field_count)
-                }
-            }
+    /// Views the struct as a one-dimensional `ndarray` array of its fields.
+    #[cfg(feature = "ndarray")]
+    fn as_ndarray_view(&self) -> ArrayView1<'_, Self::Elem> {
+        ArrayView1::from(self.as_slice())
+    }
+
+    /// Views the struct mutably as a one-dimensional `ndarray` array of its
+    /// fields.
+    #[cfg(feature = "ndarray")]
+    fn as_ndarray_view_mut(&mut self) -> ArrayViewMut1<'_, Self::Elem> {
+        ArrayViewMut1::from(self.as_mut_slice())
+    }
+
+    /// Views the slice of structs as a two-dimensional `ndarray` array of
+    /// shape `(slice.len(), Self::LEN)`, with a row for each struct.
+    #[cfg(feature = "ndarray")]
+    fn slice_as_ndarray_view(slice: &[Self]) -> ArrayView2<'_, Self::Elem> {
+        // The shape always matches the length of the flat slice.
+        ArrayView2::from_shape((slice.len(), Self::LEN), Self::as_flat_slice(slice)).unwrap()
+    }
+
+    /// Views the slice of structs mutably as a two-dimensional `ndarray` array
+    /// of shape `(slice.len(), Self::LEN)`, with a row for each struct.
+    #[cfg(feature = "ndarray")]
+    fn slice_as_ndarray_view_mut(slice: &mut [Self]) -> ArrayViewMut2<'_, Self::Elem> {
+        let shape = (slice.len(), Self::LEN);
+        // The shape always matches the length of the flat slice.
+        ArrayViewMut2::from_shape(shape, Self::as_flat_slice_mut(slice)).unwrap()
+    }
+
+    /// Views a two-dimensional `ndarray` array as a slice of structs, one for
+    /// each row.
+    ///
+    /// Returns an error of kind `IncompatibleShape` if the number of columns
+    /// is not `Self::LEN`, or of kind `IncompatibleLayout` if the array is
+    /// not contiguous in standard (row-major) order.
+    #[cfg(feature = "ndarray")]
+    fn slice_from_ndarray_view(view: ArrayView2<'_, Self::Elem>) -> Result<&[Self], ShapeError> {
+        if view.ncols() != Self::LEN {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape));
         }
+        let flat = view.to_slice().ok_or_else(|| ShapeError::from_kind(ErrorKind::IncompatibleLayout))?;
+        Ok(Self::from_flat_slice(flat))
+    }
 
-        impl<'a> #impl_generics From<&'a [#field_type]> for &'a #name #ty_generics #where_clause {
-            #[doc=#from_slice_doc]
-            fn from(slice: &'a [#field_type]) -> &'a #name {
-                assert_eq!(slice.len(), #field_count);
-                unsafe {
-                    &*(slice.as_ptr() as *const #name)
-                }
-            }
+    /// Views a mutable two-dimensional `ndarray` array as a mutable slice of
+    /// structs, one for each row.
+    ///
+    /// Returns an error of kind `IncompatibleShape` if the number of columns
+    /// is not `Self::LEN`, or of kind `IncompatibleLayout` if the array is
+    /// not contiguous in standard (row-major) order.
+    #[cfg(feature = "ndarray")]
+    fn slice_from_ndarray_view_mut(view: ArrayViewMut2<'_, Self::Elem>) -> Result<&mut [Self], ShapeError> {
+        if view.ncols() != Self::LEN {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape));
         }
+        let flat = view.into_slice().ok_or_else(|| ShapeError::from_kind(ErrorKind::IncompatibleLayout))?;
+        Ok(Self::from_flat_slice_mut(flat))
+    }
 
-        impl #impl_generics ::std::convert::AsRef<#name> for [#field_type] #ty_generics #where_clause {
-            #[doc=#from_slice_doc]
-            fn as_ref(&self) -> &#name {
-                assert_eq!(self.len(), #field_count);
-                unsafe {
-                    &*(self.as_ptr() as *const #name)
-                }
+    /// Converts each struct in the slice in place into another struct array
+    /// with the same element type and length, and returns the slice viewed
+    /// as a slice of the other struct array.
+    ///
+    /// This is useful with the conversions generated by the
+    /// `#[struct_array(convert_to(...))]` attribute, which reorder the fields,
+    /// e.g. to convert a slice of `Rgb` pixels to `Bgr` without allocating.
+    ///
+    /// It is a compile-time error if the lengths differ. The process is
+    /// aborted if a conversion panics, since the slice would otherwise be left
+    /// with a moved-from struct.
+    fn convert_slice_in_place<T>(slice: &mut [Self]) -> &mut [T]
+    where
+        T: StructArray<Elem = Self::Elem> + From<Self>,
+    {
+        let () = __private::assert_same_shape::<Self, T>();
+        let guard = __private::AbortOnPanic;
+        for s in slice.iter_mut() {
+            unsafe {
+                let converted = T::from(ptr::read(s));
+                ptr::write(__private::cast_mut::<Self, _, T>(s), converted);
             }
         }
+        mem::forget(guard);
+        Self::cast_slice_mut(slice)
+    }
+}
+
+/// Reads a struct array of primitives in little-endian (`little == true`) or
+/// big-endian byte order.
+fn read_array<S, R>(reader: R, little: bool) -> io::Result<S>
+where
+    S: StructArray,
+    S::Elem: Primitive,
+    R: io::Read,
+{
+    // All zeros is a valid value for an array of primitives.
+    let mut array: S::Array = unsafe { mem::zeroed() };
+    read_flat_slice(array.as_mut(), reader, little)?;
+    Ok(S::from_array(array))
+}
 
-        impl<'a> #impl_generics From<&'a mut #name> for &'a mut [#field_type] #ty_generics #where_clause {
-            fn from(s: &'a mut #name) -> &'a mut [#field_type] {
-                unsafe {
-                    ::std::slice::from_raw_parts_mut(s as *mut #name as *mut #field_type, #field_count)
-                }
+/// Fills the primitives with bytes from the reader, in little-endian (`little
+/// == true`) or big-endian byte order.
+fn read_flat_slice<T, R>(values: &mut [T], mut reader: R, little: bool) -> io::Result<()>
+where
+    T: Primitive,
+    R: io::Read,
+{
+    reader.read_exact(primitive::as_bytes_mut(values))?;
+    primitive::convert_endian(values, little);
+    Ok(())
+}
+
+/// Writes the primitives to the writer in little-endian (`little == true`) or
+/// big-endian byte order.
+fn write_flat_slice<T, W>(values: &[T], mut writer: W, little: bool) -> io::Result<()>
+where
+    T: Primitive,
+    W: io::Write,
+{
+    if little == cfg!(target_endian = "little") {
+        writer.write_all(primitive::as_bytes(values))
+    } else {
+        // Convert in chunks to avoid allocating a copy of the whole slice.
+        let mut buf = [0u8; 4096];
+        let size = mem::size_of::<T>();
+        for chunk in values.chunks(buf.len() / size) {
+            let bytes = &mut buf[..mem::size_of_val(chunk)];
+            for (value, dst) in chunk.iter().zip(bytes.chunks_mut(size)) {
+                dst.copy_from_slice(primitive::as_bytes(&[value.swap_bytes()]));
             }
+            writer.write_all(bytes)?;
         }
+        Ok(())
+    }
+}
 
-        impl #impl_generics ::std::convert::AsMut<[#field_type]> for #name #ty_generics #where_clause {
-            fn as_mut(&mut self) -> &mut [#field_type] {
-                unsafe {
-                    ::std::slice::from_raw_parts_mut(self as *mut #name as *mut #field_type, #field_count)
-                }
-            }
+/// A struct array that can be viewed as a flat array of scalars.
+///
+/// This trait is implemented by each of the derive macros in this crate. For
+/// most structs, the scalars are just the fields, so `Self::Scalar` is
+/// `Self::Elem` and `Self::FLAT_LEN` is `Self::LEN`. With the
+/// `#[struct_array(flatten)]` attribute, the fields must themselves implement
+/// `FlatStructArray`, and the scalars are the scalars of all the fields in
+/// order, so struct arrays can be nested to any depth:
+///
+/// ```
+/// #[macro_use]
+/// extern crate struct_array;
+///
+/// use struct_array::FlatStructArray;
+///
+/// #[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+/// #[repr(C)]
+/// struct Vec3 {
+///     pub x: f32,
+///     pub y: f32,
+///     pub z: f32,
+/// }
+///
+/// #[derive(Debug,PartialEq,StructArray)]
+/// #[repr(C)]
+/// #[struct_array(flatten)]
+/// struct Aabb {
+///     pub min: Vec3,
+///     pub max: Vec3,
+/// }
+///
+/// fn main() {
+///     let aabb = Aabb {
+///         min: Vec3 { x: 0., y: 1., z: 2. },
+///         max: Vec3 { x: 3., y: 4., z: 5. },
+///     };
+///     assert_eq!(aabb.len(), 2);
+///     assert_eq!(aabb.as_flat_array(), &[0., 1., 2., 3., 4., 5.]);
+///     let slice: &[f32] = aabb.as_ref();
+///     assert_eq!(slice.len(), 6);
+/// }
+/// ```
+///
+/// # Safety
+///
+/// `Self` must have the same size as `Self::FlatArray`, which must be
+/// `[Self::Scalar; Self::FLAT_LEN]`, and the same alignment unless it is
+/// zero-sized, and the value of every scalar must be valid at the
+/// corresponding index of the flat array.
+pub unsafe trait FlatStructArray: StructArray {
+    /// Type of the scalars.
+    type Scalar;
+
+    /// Flat array with the same layout as the struct,
+    /// `[Self::Scalar; Self::FLAT_LEN]`.
+    type FlatArray: AsRef<[Self::Scalar]> + AsMut<[Self::Scalar]>;
+
+    /// Total number of scalars.
+    const FLAT_LEN: usize;
+
+    /// Checks the layout at compile time, like
+    /// `StructArray::__ASSERT_LAYOUT`.
+    #[doc(hidden)]
+    const __ASSERT_FLAT_LAYOUT: () = __private::assert_flat_layout::<Self>();
+
+    /// Converts the struct into a flat array.
+    fn into_flat_array(self) -> Self::FlatArray {
+        let () = Self::__ASSERT_FLAT_LAYOUT;
+        let s = ManuallyDrop::new(self);
+        unsafe { ptr::read(__private::cast::<Self, _, Self::FlatArray>(&*s)) }
+    }
+
+    /// Converts a flat array into the struct.
+    fn from_flat_array(array: Self::FlatArray) -> Self {
+        let () = Self::__ASSERT_FLAT_LAYOUT;
+        let array = ManuallyDrop::new(array);
+        unsafe { ptr::read(__private::cast::<Self, _, Self>(&*array)) }
+    }
+
+    /// Returns a reference to the struct as a flat array.
+    fn as_flat_array(&self) -> &Self::FlatArray {
+        let () = Self::__ASSERT_FLAT_LAYOUT;
+        unsafe { &*__private::cast::<Self, _, Self::FlatArray>(self) }
+    }
+
+    /// Returns a mutable reference to the struct as a flat array.
+    fn as_flat_array_mut(&mut self) -> &mut Self::FlatArray {
+        let () = Self::__ASSERT_FLAT_LAYOUT;
+        unsafe { &mut *__private::cast_mut::<Self, _, Self::FlatArray>(self) }
+    }
+
+    /// Returns a slice of the scalars.
+    fn as_scalar_slice(&self) -> &[Self::Scalar] {
+        let () = Self::__ASSERT_FLAT_LAYOUT;
+        unsafe {
+            slice::from_raw_parts(__private::cast::<Self, _, Self::Scalar>(self), Self::FLAT_LEN)
         }
+    }
 
-        impl<'a> #impl_generics From<&'a mut [#field_type]> for &'a mut #name #ty_generics #where_clause {
-            #[doc=#from_slice_doc]
-            fn from(slice: &'a mut [#field_type]) -> &'a mut #name {
-                assert_eq!(slice.len(), #field_count);
-                unsafe {
-                    &mut *(slice.as_mut_ptr() as *mut #name)
-                }
-            }
+    /// Returns a mutable slice of the scalars.
+    fn as_scalar_slice_mut(&mut self) -> &mut [Self::Scalar] {
+        let () = Self::__ASSERT_FLAT_LAYOUT;
+        unsafe {
+            slice::from_raw_parts_mut(__private::cast_mut::<Self, _, Self::Scalar>(self),
+                                      Self::FLAT_LEN)
         }
+    }
+}
+
+/// A zero-sized type whose values can be created out of nothing, which can be
+/// the type of a field excluded from the array with `#[struct_array(skip)]`.
+///
+/// This is implemented for `PhantomData`, `PhantomPinned` and `()`.
+///
+/// # Safety
+///
+/// `Self` must be zero-sized, and creating a value of it without calling any
+/// of its constructors (e.g. by viewing an array as the struct) must not break
+/// any of its invariants. In particular, this must not be implemented for
+/// tokens whose values prove that some code has run.
+pub unsafe trait Marker {}
+
+unsafe impl<T: ?Sized> Marker for PhantomData<T> {}
 
-        impl #impl_generics ::std::convert::AsMut<#name> for [#field_type] #ty_generics #where_clause {
-            #[doc=#from_slice_doc]
-            fn as_mut(&mut self) -> &mut #name {
-                assert_eq!(self.len(), #field_count);
-                unsafe {
-                    &mut *(self.as_mut_ptr() as *mut #name)
-                }
+unsafe impl Marker for PhantomPinned {}
+
+unsafe impl Marker for () {}
+
+/// Error converting an array into a struct with the `from_array` method
+/// generated by `#[derive(StructArrayCopy)]`, when a value could not be
+/// converted to the type of its field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldConversionError {
+    field: &'static str,
+}
+
+impl FieldConversionError {
+    /// Returns the name (or index, for tuple structs) of the field whose value
+    /// could not be converted.
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+}
+
+impl fmt::Display for FieldConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the value for field `{}` could not be converted to its type", self.field)
+    }
+}
+
+impl Error for FieldConversionError {}
+
+/// The error type for the byte views of struct arrays, returned by
+/// `StructArray::try_from_bytes` and `StructArray::try_slice_from_bytes`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteViewError {
+    /// The length of the bytes is not the size of the struct (or a multiple
+    /// of it, for a slice of structs).
+    Length,
+    /// The bytes are not aligned for the element type.
+    Alignment,
+}
+
+impl fmt::Display for ByteViewError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ByteViewError::Length => {
+                write!(f, "the length of the bytes does not match the size of the struct array")
             }
+            ByteViewError::Alignment => write!(f, "the bytes are not aligned for the struct array"),
         }
     }
 }
+
+impl Error for ByteViewError {}
@@ -0,0 +1,72 @@
+//! Primitive numeric element types.
+
+use std::{mem, slice};
+
+/// A primitive numeric type, which has no padding bytes and for which every
+/// bit pattern is a valid value, so struct arrays of it can be read and
+/// written as bytes.
+///
+/// This trait is implemented for all the integer types of a fixed size and
+/// for `f32` and `f64`.
+///
+/// # Safety
+///
+/// The type must have no padding bytes, and every bit pattern of its size
+/// must be a valid value.
+pub unsafe trait Primitive: Copy {
+    /// Reverses the byte order of the value.
+    fn swap_bytes(self) -> Self;
+}
+
+macro_rules! impl_primitive_int {
+    ($($t:ty),*) => {
+        $(
+            unsafe impl Primitive for $t {
+                fn swap_bytes(self) -> $t {
+                    <$t>::swap_bytes(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+unsafe impl Primitive for f32 {
+    fn swap_bytes(self) -> f32 {
+        f32::from_bits(self.to_bits().swap_bytes())
+    }
+}
+
+unsafe impl Primitive for f64 {
+    fn swap_bytes(self) -> f64 {
+        f64::from_bits(self.to_bits().swap_bytes())
+    }
+}
+
+/// Views a slice of primitives as bytes.
+pub fn as_bytes<T: Primitive>(values: &[T]) -> &[u8] {
+    unsafe { slice::from_raw_parts(values.as_ptr() as *const u8, mem::size_of_val(values)) }
+}
+
+/// Views a mutable slice of primitives as bytes.
+pub fn as_bytes_mut<T: Primitive>(values: &mut [T]) -> &mut [u8] {
+    unsafe {
+        slice::from_raw_parts_mut(values.as_mut_ptr() as *mut u8, mem::size_of_val(values))
+    }
+}
+
+/// Reverses the byte order of each of the values in place.
+pub fn swap_bytes<T: Primitive>(values: &mut [T]) {
+    for value in values {
+        *value = value.swap_bytes();
+    }
+}
+
+/// Converts the values in place between the native byte order and little
+/// endian (`little == true`) or big endian.
+pub fn convert_endian<T: Primitive>(values: &mut [T], little: bool) {
+    if little != cfg!(target_endian = "little") {
+        swap_bytes(values);
+    }
+}
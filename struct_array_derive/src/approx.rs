@@ -0,0 +1,69 @@
+//! Generation of the `approx` trait implementations for the
+//! `#[struct_array(approx)]` option.
+
+use quote;
+
+use input::StructInfo;
+
+/// Implements `AbsDiffEq`, `RelativeEq` and `UlpsEq`, if enabled by the
+/// `#[struct_array(approx)]` option.
+///
+/// The implementations delegate to the ones for slices of the element type,
+/// which compare the elements pairwise.
+pub fn impl_approx(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, generics, ref field_type, ref options, .. } = *struct_info;
+    if !options.approx {
+        return quote!();
+    }
+    let approx = quote!(::struct_array::__private::approx);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = &generics.where_clause.predicates;
+    let epsilon = quote!(<#field_type as #approx::AbsDiffEq>::Epsilon);
+    quote! {
+        impl #impl_generics #approx::AbsDiffEq for #name #ty_generics
+            where #(#predicates,)* #field_type: #approx::AbsDiffEq, #epsilon: ::std::clone::Clone
+        {
+            type Epsilon = #epsilon;
+
+            fn default_epsilon() -> #epsilon {
+                <#field_type as #approx::AbsDiffEq>::default_epsilon()
+            }
+
+            fn abs_diff_eq(&self, other: &Self, epsilon: #epsilon) -> bool {
+                #approx::AbsDiffEq::abs_diff_eq(::struct_array::StructArray::as_slice(self),
+                                                ::struct_array::StructArray::as_slice(other),
+                                                epsilon)
+            }
+        }
+
+        impl #impl_generics #approx::RelativeEq for #name #ty_generics
+            where #(#predicates,)* #field_type: #approx::RelativeEq, #epsilon: ::std::clone::Clone
+        {
+            fn default_max_relative() -> #epsilon {
+                <#field_type as #approx::RelativeEq>::default_max_relative()
+            }
+
+            fn relative_eq(&self, other: &Self, epsilon: #epsilon, max_relative: #epsilon) -> bool {
+                #approx::RelativeEq::relative_eq(::struct_array::StructArray::as_slice(self),
+                                                 ::struct_array::StructArray::as_slice(other),
+                                                 epsilon,
+                                                 max_relative)
+            }
+        }
+
+        impl #impl_generics #approx::UlpsEq for #name #ty_generics
+            where #(#predicates,)* #field_type: #approx::UlpsEq, #epsilon: ::std::clone::Clone
+        {
+            fn default_max_ulps() -> u32 {
+                <#field_type as #approx::UlpsEq>::default_max_ulps()
+            }
+
+            fn ulps_eq(&self, other: &Self, epsilon: #epsilon, max_ulps: u32) -> bool {
+                #approx::UlpsEq::ulps_eq(::struct_array::StructArray::as_slice(self),
+                                         ::struct_array::StructArray::as_slice(other),
+                                         epsilon,
+                                         max_ulps)
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Generation of the `bytemuck` trait implementations for the
+//! `#[struct_array(bytemuck)]` option.
+
+use quote;
+
+use input::StructInfo;
+
+/// Implements `Zeroable` and `Pod`, if enabled by the
+/// `#[struct_array(bytemuck)]` option.
+///
+/// The implementations are sound because every field (including the skipped
+/// ones) is asserted to be `Pod`, and the layout check, which is evaluated as
+/// a constant here, ensures there is no padding between the fields.
+pub fn impl_bytemuck(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, ref fields, ref options, .. } = *struct_info;
+    if !options.bytemuck {
+        return quote!();
+    }
+    let bytemuck = quote!(::struct_array::__private::bytemuck);
+    let field_types: Vec<_> = fields.iter().map(|field| field.ty).collect();
+    quote! {
+        const _: () = {
+            fn assert_pod<T: #bytemuck::Pod>() {}
+            let _: fn() = || {
+                #(assert_pod::<#field_types>();)*
+            };
+            ::struct_array::__private::assert_layout::<#name>()
+        };
+
+        unsafe impl #bytemuck::Zeroable for #name {}
+
+        unsafe impl #bytemuck::Pod for #name {}
+    }
+}
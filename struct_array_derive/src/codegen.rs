@@ -0,0 +1,412 @@
+//! Generation of the trait implementations.
+//!
+//! The only `unsafe` code in the expansion is the implementation of the
+//! `StructArray` marker trait. Coherence rules out blanket implementations of
+//! the standard library conversion traits in the runtime crate, so they are
+//! still implemented for each struct, but by invoking a macro from the
+//! runtime crate for each group of conversions, which casts between the
+//! struct and its array with the helpers in `struct_array::__private`.
+
+use quote;
+use quote::ToTokens;
+use syn;
+
+use approx::impl_approx;
+use bytemuck::impl_bytemuck;
+use glam::impl_glam;
+use input::{Len, StructInfo};
+use mint::impl_mint;
+use nalgebra::impl_nalgebra;
+use num_traits::impl_num_traits;
+use ops::impl_ops;
+use serde::impl_serde;
+use swizzle::impl_swizzles;
+
+/// Returns a copy of `generics` with the lifetime `'a` added, for
+/// implementations on references.
+fn with_lifetime_a(generics: &syn::Generics) -> syn::Generics {
+    let mut generics = generics.clone();
+    generics.lifetimes.insert(0, syn::LifetimeDef::new("'a"));
+    generics
+}
+
+/// Returns the `where` clause of the struct with bounds requiring the types of
+/// the skipped fields to implement `Marker`, for the implementations that view
+/// arrays as the struct without relying on the `StructArray` trait.
+fn where_clause_with_markers(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { generics, ref fields, .. } = *struct_info;
+    let predicates = &generics.where_clause.predicates;
+    let markers: Vec<_> = fields.iter().filter(|field| field.skip).map(|field| field.ty).collect();
+    if predicates.is_empty() && markers.is_empty() {
+        return quote!();
+    }
+    quote!(where #(#predicates,)* #(#markers: ::struct_array::Marker,)*)
+}
+
+/// Returns the scalar type and the number of scalars in the flattened struct.
+fn flat_scalar_and_len(struct_info: &StructInfo) -> (quote::Tokens, quote::Tokens) {
+    let StructInfo { ref field_type, ref field_count, ref options, .. } = *struct_info;
+    if options.flatten {
+        let inner = quote!(<#field_type as ::struct_array::FlatStructArray>);
+        (quote!(#inner::Scalar), quote!(#field_count * #inner::FLAT_LEN))
+    } else {
+        (quote!(#field_type), quote!(#field_count))
+    }
+}
+
+/// Implements the `StructArray` trait along with the associated constants
+/// and the items enabled by the `#[struct_array(...)]` options.
+///
+/// This is the part of the output of `#[derive(StructArray)]` that the other
+/// derives do not generate.
+pub fn impl_struct_array_trait_and_options(struct_info: &StructInfo) -> quote::Tokens {
+    let mut expanded = quote::Tokens::new();
+    impl_struct_array_trait(struct_info).to_tokens(&mut expanded);
+    impl_index_consts(struct_info).to_tokens(&mut expanded);
+    impl_swizzles(struct_info).to_tokens(&mut expanded);
+    impl_views(struct_info).to_tokens(&mut expanded);
+    impl_extends(struct_info).to_tokens(&mut expanded);
+    impl_convert_to(struct_info).to_tokens(&mut expanded);
+    impl_ops(struct_info).to_tokens(&mut expanded);
+    impl_serde(struct_info).to_tokens(&mut expanded);
+    impl_bytemuck(struct_info).to_tokens(&mut expanded);
+    impl_mint(struct_info).to_tokens(&mut expanded);
+    impl_nalgebra(struct_info).to_tokens(&mut expanded);
+    impl_glam(struct_info).to_tokens(&mut expanded);
+    impl_approx(struct_info).to_tokens(&mut expanded);
+    impl_num_traits(struct_info).to_tokens(&mut expanded);
+    expanded
+}
+
+/// Implements the `StructArray` and `FlatStructArray` traits from the runtime
+/// crate.
+fn impl_struct_array_trait(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, generics, ref field_type, ref field_count, .. } = *struct_info;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (scalar, flat_len) = flat_scalar_and_len(struct_info);
+    let skipped = if struct_info.fields.iter().any(|field| field.skip) {
+        impl_skipped_fields(struct_info)
+    } else {
+        quote!()
+    };
+    quote! {
+        unsafe impl #impl_generics ::struct_array::StructArray for #name #ty_generics #where_clause {
+            type Elem = #field_type;
+            type Array = [#field_type; #field_count];
+            const LEN: usize = #field_count;
+
+            #skipped
+        }
+
+        unsafe impl #impl_generics ::struct_array::FlatStructArray for #name #ty_generics #where_clause {
+            type Scalar = #scalar;
+            type FlatArray = [#scalar; #flat_len];
+            const FLAT_LEN: usize = #flat_len;
+        }
+    }
+}
+
+/// Implements the items of the `StructArray` trait that need to account for
+/// fields excluded from the array: the layout check asserts that their types
+/// implement `Marker`, and `from_array` fills them in with their default
+/// values.
+fn impl_skipped_fields(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { ref field_type, ref field_count, ref fields, .. } = *struct_info;
+    let mut asserts = Vec::new();
+    let mut members = Vec::new();
+    let mut values = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let member = match field.ident {
+            Some(ident) => ident.clone(),
+            None => syn::Ident::new(index),
+        };
+        if field.skip {
+            let ty = field.ty;
+            asserts.push(quote! {
+                ::struct_array::__private::assert_marker::<#ty>();
+            });
+            values.push(quote!(::std::default::Default::default()));
+        } else if field.array_len.is_some() {
+            values.push(quote!(::std::array::from_fn(|_| elems.next().unwrap())));
+        } else {
+            values.push(quote!(elems.next().unwrap()));
+        }
+        members.push(member);
+    }
+    quote! {
+        const __ASSERT_LAYOUT: () = {
+            #(#asserts)*
+            ::struct_array::__private::assert_layout::<Self>()
+        };
+
+        #[allow(unused_mut, unused_variables)]
+        fn from_array(array: [#field_type; #field_count]) -> Self {
+            let () = <Self as ::struct_array::StructArray>::__ASSERT_LAYOUT;
+            let mut elems = ::std::iter::IntoIterator::into_iter(array);
+            Self { #(#members: #values),* }
+        }
+    }
+}
+
+/// Implements associated constants with the index in the array of each field
+/// of a struct with named fields.
+fn impl_index_consts(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, generics, ref fields, .. } = *struct_info;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut index = Len::default();
+    let mut consts = Vec::new();
+    for field in fields.iter().filter(|field| !field.skip) {
+        if let Some(ident) = field.ident {
+            let const_name = syn::Ident::new(format!("{}_INDEX", ident.as_ref().to_uppercase()));
+            let doc = if field.array_len.is_some() {
+                format!("Index of the first element of the `{}` field in the array.", ident)
+            } else {
+                format!("Index of the `{}` field in the array.", ident)
+            };
+            consts.push(quote! {
+                #[doc=#doc]
+                pub const #const_name: usize = #index;
+            });
+        }
+        index.add(field.array_len);
+    }
+    if consts.is_empty() {
+        return quote!();
+    }
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#consts)*
+        }
+    }
+}
+
+/// Converts a type name like `LinearRgb` to snake case like `linear_rgb`.
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_uppercase() && prev_lower {
+            snake.push('_');
+        }
+        prev_lower = c.is_lowercase() || c.is_numeric();
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// Implements the `as_*` and `as_*_mut` methods for the views of ranges of
+/// fields given with the `#[struct_array(view(...))]` option.
+fn impl_views(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, generics, ref fields, ref views, .. } = *struct_info;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut methods = Vec::new();
+    for view in views {
+        let mut offset = Len::default();
+        let mut count = Len::default();
+        for (index, field) in fields.iter().enumerate().filter(|&(_, field)| !field.skip) {
+            if index < view.fields.start {
+                offset.add(field.array_len);
+            } else if index < view.fields.end {
+                count.add(field.array_len);
+            }
+        }
+        let target = &view.target;
+        let method = syn::Ident::new(format!("as_{}", to_snake_case(target.as_ref())));
+        let method_mut = syn::Ident::new(format!("{}_mut", method));
+        let doc = format!("Views the `{}` fields as a `{}`.", view.source, target);
+        let doc_mut = format!("Views the `{}` fields mutably as a `{}`.", view.source, target);
+        let message = format!("`{}` must have {} elements to view the `{}` fields",
+                              target, count, view.source);
+        let check = quote! {
+            const {
+                assert!(<#target as ::struct_array::StructArray>::LEN == #count, #message);
+            }
+        };
+        methods.push(quote! {
+            #[doc=#doc]
+            pub fn #method(&self) -> &#target {
+                #check;
+                let slice = ::struct_array::StructArray::as_slice(self);
+                ::struct_array::StructArray::from_slice(&slice[#offset..#offset + #count])
+            }
+
+            #[doc=#doc_mut]
+            pub fn #method_mut(&mut self) -> &mut #target {
+                #check;
+                let slice = ::struct_array::StructArray::as_mut_slice(self);
+                ::struct_array::StructArray::from_slice_mut(&mut slice[#offset..#offset + #count])
+            }
+        });
+    }
+    if methods.is_empty() {
+        return quote!();
+    }
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#methods)*
+        }
+    }
+}
+
+/// Implements the conversions between the struct and the shorter struct arrays
+/// it extends, given with the `#[struct_array(extend(...))]` option.
+fn impl_extends(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, ref field_type, ref fields, ref extends, .. } = *struct_info;
+    let mut impls = Vec::new();
+    for extend in extends {
+        let mut prefix_len = Len::default();
+        let mut rest = Vec::new();
+        let mut rest_len = Len::default();
+        for (index, field) in fields.iter().enumerate().filter(|&(_, field)| !field.skip) {
+            if index < extend.fields.end {
+                prefix_len.add(field.array_len);
+            } else {
+                rest_len.add(field.array_len);
+                rest.push(match field.ident {
+                    Some(ident) => ident.to_string(),
+                    None => index.to_string(),
+                });
+            }
+        }
+        // The rest is passed as a single element if it is a single field that
+        // is not an array, or as an array otherwise.
+        let single = rest_len.exprs.is_empty() && rest_len.known == 1 && rest.len() == 1;
+        let (rest_ty, rest_iter, rest_value) = if single {
+            (quote!(#field_type),
+             quote!(::std::iter::once(rest)),
+             quote!(elems.next().unwrap()))
+        } else {
+            (quote!([#field_type; #rest_len]),
+             quote!(::std::iter::IntoIterator::into_iter(rest)),
+             quote!(::std::array::from_fn(|_| elems.next().unwrap())))
+        };
+        let target = &extend.target;
+        let extend_doc = format!("Extends the `{}` to a `{}` with the `{}` fields.",
+                                 target, name, rest.join("`, `"));
+        let truncate_doc = format!("Truncates the `{}` to a `{}` of the `{}` fields.",
+                                   name, target, extend.source);
+        let message = format!("`{}` must have {} elements to be extended to `{}`",
+                              target, prefix_len, name);
+        let check = quote! {
+            const {
+                assert!(<#target as ::struct_array::StructArray>::LEN == #prefix_len, #message);
+            }
+        };
+        impls.push(quote! {
+            impl #target {
+                #[doc=#extend_doc]
+                pub fn extend(self, rest: #rest_ty) -> #name {
+                    ::std::convert::From::from((self, rest))
+                }
+            }
+
+            impl #name {
+                #[doc=#truncate_doc]
+                pub fn truncate(self) -> #target {
+                    <(#target, #rest_ty) as ::std::convert::From<#name>>::from(self).0
+                }
+            }
+
+            impl ::std::convert::From<(#target, #rest_ty)> for #name {
+                fn from((head, rest): (#target, #rest_ty)) -> #name {
+                    #check;
+                    let mut elems =
+                        ::std::iter::IntoIterator::into_iter(::struct_array::StructArray::into_array(head))
+                            .chain(#rest_iter);
+                    ::struct_array::StructArray::from_array(::std::array::from_fn(|_| elems.next().unwrap()))
+                }
+            }
+
+            impl ::std::convert::From<#name> for (#target, #rest_ty) {
+                fn from(value: #name) -> (#target, #rest_ty) {
+                    #check;
+                    let mut elems =
+                        ::std::iter::IntoIterator::into_iter(::struct_array::StructArray::into_array(value));
+                    let head = ::struct_array::StructArray::from_array(
+                        ::std::array::from_fn(|_| elems.next().unwrap()));
+                    (head, #rest_value)
+                }
+            }
+        });
+    }
+    quote!(#(#impls)*)
+}
+
+/// Implements the conversions to the struct arrays given with the
+/// `#[struct_array(convert_to(...))]` option, which have the same field names
+/// in a different order.
+///
+/// The conversion is a struct literal, so the compiler reports any field
+/// names that are missing from or not in the struct.
+fn impl_convert_to(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, ref fields, ref options, .. } = *struct_info;
+    let members: Vec<_> = fields.iter().filter_map(|field| field.ident).collect();
+    let impls = options.convert_to.iter().map(|target| {
+        let (members, values) = (&members, &members);
+        quote! {
+            impl ::std::convert::From<#name> for #target {
+                fn from(value: #name) -> #target {
+                    #target {
+                        #(#members: value.#values,)*
+                    }
+                }
+            }
+        }
+    });
+    quote!(#(#impls)*)
+}
+
+/// Implements `Deref` and `DerefMut` to the array.
+pub fn impl_struct_array_deref(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, generics, ref field_type, ref field_count, .. } = *struct_info;
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let where_clause = where_clause_with_markers(struct_info);
+    quote! {
+        ::struct_array::__impl_struct_array_deref!(
+            [#impl_generics] #name #ty_generics, [#field_type; #field_count], [#where_clause]);
+    }
+}
+
+/// Implements the conversions to/from arrays and slices.
+pub fn impl_struct_array_convert(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, generics, ref field_type, ref field_count, ref options, .. } = *struct_info;
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let where_clause = where_clause_with_markers(struct_info);
+    let generics_a = with_lifetime_a(generics);
+    let (impl_generics_a, _, _) = generics_a.split_for_impl();
+    let from_slice_doc = format!("
+Performs the conversion.
+
+# Panics
+
+Panics if the `len()` of the slice is not {}.
+", field_count);
+    let flat = if options.flatten {
+        impl_flat_convert(struct_info)
+    } else {
+        quote!()
+    };
+    quote! {
+        #flat
+
+        ::struct_array::__impl_struct_array_convert!(
+            [#impl_generics] [#impl_generics_a] 'a, #name #ty_generics,
+            [#field_type; #field_count], #from_slice_doc, [#where_clause]);
+    }
+}
+
+/// Implements the conversions to/from flat arrays and slices of scalars for
+/// structs with the `flatten` option.
+fn impl_flat_convert(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, generics, .. } = *struct_info;
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let where_clause = where_clause_with_markers(struct_info);
+    let generics_a = with_lifetime_a(generics);
+    let (impl_generics_a, _, _) = generics_a.split_for_impl();
+    let (scalar, flat_len) = flat_scalar_and_len(struct_info);
+    quote! {
+        ::struct_array::__impl_flat_struct_array_convert!(
+            [#impl_generics] [#impl_generics_a] 'a, #name #ty_generics, [#scalar; #flat_len],
+            [#where_clause]);
+    }
+}
@@ -0,0 +1,68 @@
+//! Generation of the copying conversions for `#[derive(StructArrayCopy)]`.
+//!
+//! Unlike the other derives, these convert each field by value, so the fields
+//! may have differing types and the struct doesn't need `#[repr(C)]`.
+
+use quote;
+use syn;
+
+use input::CopyStructInfo;
+
+/// Implements `to_array` and `from_array` as inherent methods.
+pub fn impl_struct_array_copy(struct_info: &CopyStructInfo) -> quote::Tokens {
+    let CopyStructInfo { name, generics, ref fields } = *struct_info;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut elements = Vec::new();
+    let mut types = Vec::new();
+    let mut members = Vec::new();
+    let mut values = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let member = match field.ident {
+            Some(ident) => ident.clone(),
+            None => syn::Ident::new(index),
+        };
+        if field.skip {
+            values.push(quote!(::std::default::Default::default()));
+        } else {
+            let var = syn::Ident::new(format!("v{}", elements.len()));
+            let message = member.to_string();
+            values.push(quote! {
+                ::std::convert::TryFrom::try_from(#var)
+                    .map_err(|_| ::struct_array::__private::field_conversion_error(#message))?
+            });
+            elements.push((member.clone(), var));
+            types.push(field.ty);
+        }
+        members.push(member);
+    }
+    let len = elements.len();
+    let element_members: Vec<_> = elements.iter().map(|(member, _)| member).collect();
+    let vars: Vec<_> = elements.iter().map(|(_, var)| var).collect();
+    let types2 = types.clone();
+    let doc_to = "Copies the fields into an array of `U`, converting each one with `Into<U>`.";
+    let doc_from = format!("Creates a `{}` from an array of `U`, converting each value with \
+                            `TryFrom<U>` to the type of its field.",
+                           name);
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc=#doc_to]
+            pub fn to_array<U>(&self) -> [U; #len]
+                where #(#types: ::std::clone::Clone + ::std::convert::Into<U>),*
+            {
+                [#(::std::convert::Into::into(::std::clone::Clone::clone(&self.#element_members))),*]
+            }
+
+            #[doc=#doc_from]
+            pub fn from_array<U>(array: [U; #len])
+                                 -> ::std::result::Result<Self, ::struct_array::FieldConversionError>
+                where #(#types2: ::std::convert::TryFrom<U>),*
+            {
+                #[allow(unused_variables)]
+                let [#(#vars),*] = array;
+                ::std::result::Result::Ok(#name {
+                    #(#members: #values,)*
+                })
+            }
+        }
+    }
+}
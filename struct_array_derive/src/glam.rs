@@ -0,0 +1,34 @@
+//! Generation of the `glam` conversions for the `#[struct_array(glam)]`
+//! option.
+
+use quote;
+
+use input::StructInfo;
+
+/// Implements `From` in both directions between the struct and each `glam`
+/// type with its element type and number of elements.
+///
+/// The conversions copy the elements through the arrays rather than
+/// reinterpreting the struct, since types like `Vec3A` have a different
+/// alignment and size.
+pub fn impl_glam(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, generics, ref glam, .. } = *struct_info;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let impls = glam.iter().map(|target| {
+        let target = quote!(::struct_array::__private::glam::#target);
+        quote! {
+            impl #impl_generics ::std::convert::From<#name #ty_generics> for #target #where_clause {
+                fn from(value: #name #ty_generics) -> #target {
+                    ::std::convert::From::from(::struct_array::StructArray::into_array(value))
+                }
+            }
+
+            impl #impl_generics ::std::convert::From<#target> for #name #ty_generics #where_clause {
+                fn from(value: #target) -> #name #ty_generics {
+                    ::struct_array::StructArray::from_array(::std::convert::From::from(value))
+                }
+            }
+        }
+    });
+    quote!(#(#impls)*)
+}
@@ -0,0 +1,83 @@
+//! Generation of the accessors for `#[derive(StructArrayGroups)]`.
+//!
+//! Each group is a run of fields of the same type in a `#[repr(C)]` struct,
+//! which has no padding between the fields, so it has the same layout as an
+//! array. The accessors compute the address of the array from the offset of
+//! its first field, and the alignment of the first field and the offsets of
+//! the other fields are checked at compile time.
+
+use quote;
+use syn;
+
+use input::GroupStructInfo;
+
+/// Implements the accessors for the groups of fields.
+pub fn impl_struct_array_groups(struct_info: &GroupStructInfo) -> quote::Tokens {
+    let GroupStructInfo { name, generics, ref groups } = *struct_info;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut methods = Vec::new();
+    for group in groups {
+        let ty = group.ty;
+        let len = group.members.len();
+        let first = &group.members[0];
+        let names: Vec<String> = group.members.iter().map(|member| member.to_string()).collect();
+        let getter = syn::Ident::new(group.name.clone());
+        let getter_mut = syn::Ident::new(format!("{}_mut", group.name));
+        let doc = format!("Returns a reference to the `{}` fields as an array.", names.join("`, `"));
+        let doc_mut = format!("Returns a mutable reference to the `{}` fields as an array.",
+                              names.join("`, `"));
+        let message = format!("the fields in the group `{}` must be contiguous", group.name);
+        let align_message = format!("the fields in the group `{}` must be aligned", group.name);
+        let asserts: Vec<_> = group.members
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(position, member)| {
+                quote! {
+                    assert!(::std::mem::offset_of!(Self, #member) ==
+                                ::std::mem::offset_of!(Self, #first) +
+                                    #position * ::std::mem::size_of::<#ty>(),
+                            #message);
+                }
+            })
+            .collect();
+        let check = quote! {
+            const {
+                assert!(::std::mem::align_of::<Self>() >= ::std::mem::align_of::<#ty>() &&
+                            ::std::mem::offset_of!(Self, #first) %
+                                ::std::mem::align_of::<#ty>() == 0,
+                        #align_message);
+                #(#asserts)*
+            }
+        };
+        methods.push(quote! {
+            #[doc=#doc]
+            pub fn #getter(&self) -> &[#ty; #len] {
+                #check;
+                unsafe {
+                    let ptr = (self as *const Self as *const u8)
+                        .add(::std::mem::offset_of!(Self, #first));
+                    &*(ptr as *const [#ty; #len])
+                }
+            }
+
+            #[doc=#doc_mut]
+            pub fn #getter_mut(&mut self) -> &mut [#ty; #len] {
+                #check;
+                unsafe {
+                    let ptr = (self as *mut Self as *mut u8)
+                        .add(::std::mem::offset_of!(Self, #first));
+                    &mut *(ptr as *mut [#ty; #len])
+                }
+            }
+        });
+    }
+    if methods.is_empty() {
+        return quote!();
+    }
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#methods)*
+        }
+    }
+}
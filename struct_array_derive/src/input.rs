@@ -0,0 +1,817 @@
+//! Parsing and validation of the macro input.
+
+use quote::ToTokens;
+use std::ops::Range;
+use syn;
+
+/// Errors in the input to one of the macros.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum MacroInputError {
+    ZeroFields,
+    NonpublicField,
+    DifferingFieldTypes,
+    NotStruct,
+    NotReprC,
+    Packed,
+    UnknownOption(String),
+    InvalidOption(String),
+    GenericOption(String),
+    NamedScalarFields(String),
+    UnknownField(String),
+    UnnamedFields(String),
+    NoncontiguousGroup(String),
+    MissingFeature(String),
+    NoGlamType(String),
+    SwizzleMaxLen(usize),
+}
+
+impl std::fmt::Display for MacroInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            MacroInputError::ZeroFields => write!(f, "the struct must have at least one field, or the element type must be given with #[struct_array(elem = \"...\")]"),
+            MacroInputError::NonpublicField => write!(f, "all fields in the struct must be public"),
+            MacroInputError::DifferingFieldTypes => write!(f, "all fields in the struct must have the same type (or be arrays of that type)"),
+            MacroInputError::NotStruct => write!(f, "the type must be a struct (or tuple struct), not an enum"),
+            MacroInputError::NotReprC => write!(f, "the struct must have the #[repr(C)] attribute"),
+            MacroInputError::Packed => write!(f, "the struct must not have the #[repr(packed)] attribute"),
+            MacroInputError::UnknownOption(ref option) => write!(f, "unknown option `{}` in #[struct_array(...)]", option),
+            MacroInputError::InvalidOption(ref option) => write!(f, "invalid option `{}` in #[struct_array(...)]", option),
+            MacroInputError::GenericOption(ref option) => write!(f, "#[struct_array({})] is not supported on generic structs", option),
+            MacroInputError::NamedScalarFields(ref option) => write!(f, "#[struct_array({})] requires named fields that are not arrays", option),
+            MacroInputError::UnknownField(ref field) => write!(f, "no field `{}` in the array for #[struct_array(...)]", field),
+            MacroInputError::UnnamedFields(ref option) => write!(f, "#[struct_array({})] requires named fields", option),
+            MacroInputError::NoncontiguousGroup(ref group) => write!(f, "the fields in the group `{}` must be contiguous", group),
+            // Features are named like the options, with hyphens instead of underscores.
+            MacroInputError::MissingFeature(ref option) => write!(f, "#[struct_array({})] requires the `{}` feature of struct_array", option, option.replace('_', "-")),
+            MacroInputError::NoGlamType(ref elements) => write!(f, "#[struct_array(glam)] requires 2, 3 or 4 elements of type f32, f64, i32 or u32, not {}", elements),
+            MacroInputError::SwizzleMaxLen(count) => write!(f, "#[struct_array(swizzle)] on a struct with {} fields requires `max_len`, e.g. #[struct_array(swizzle(max_len = 2))], since the number of swizzles grows exponentially", count),
+        }
+    }
+}
+
+impl std::error::Error for MacroInputError {
+    fn description(&self) -> &str {
+        match *self {
+            MacroInputError::ZeroFields => "struct had no fields",
+            MacroInputError::NonpublicField => "struct had at least one nonpublic field",
+            MacroInputError::DifferingFieldTypes => "struct had fields of differing types",
+            MacroInputError::NotStruct => "input was not a struct",
+            MacroInputError::NotReprC => "struct was missing the #[repr(C)] attribute",
+            MacroInputError::Packed => "struct had the #[repr(packed)] attribute",
+            MacroInputError::UnknownOption(_) => "struct had an unknown #[struct_array(...)] option",
+            MacroInputError::InvalidOption(_) => "struct had an invalid #[struct_array(...)] option",
+            MacroInputError::GenericOption(_) => "generic struct had an unsupported #[struct_array(...)] option",
+            MacroInputError::NamedScalarFields(_) => "struct with unnamed or array fields had an option that requires named fields that are not arrays",
+            MacroInputError::UnknownField(_) => "#[struct_array(...)] option named an unknown field",
+            MacroInputError::UnnamedFields(_) => "struct with unnamed fields had an option that requires named fields",
+            MacroInputError::NoncontiguousGroup(_) => "struct had a group of fields that were not contiguous",
+            MacroInputError::MissingFeature(_) => "struct had an option whose feature was not enabled",
+            MacroInputError::NoGlamType(_) => "struct with the glam option had no matching glam types",
+            MacroInputError::SwizzleMaxLen(_) => "struct with more than four fields had the swizzle option without max_len",
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        None
+    }
+}
+
+/// Options from `#[struct_array(...)]` attributes on the struct.
+#[derive(Clone,Debug,Default)]
+pub struct StructOptions {
+    /// Whether the fields are themselves struct arrays that should also be
+    /// viewable as one flat array of their scalars.
+    pub flatten: bool,
+    /// Element type given explicitly, e.g. for structs with zero fields.
+    pub elem: Option<syn::Ty>,
+    /// Options for swizzle accessors, if they should be generated.
+    pub swizzle: Option<SwizzleOptions>,
+    /// Types of sub-struct views and the ranges of fields they view, as
+    /// written in the option.
+    pub views: Vec<(syn::Ident, String)>,
+    /// Shorter struct arrays that this struct extends and the ranges of
+    /// fields they correspond to, as written in the option.
+    pub extends: Vec<(syn::Ident, String)>,
+    /// Struct arrays with the same field names in a different order to
+    /// convert to.
+    pub convert_to: Vec<syn::Ident>,
+    /// Whether to implement the arithmetic operators element-wise.
+    pub ops: bool,
+    /// Representation for the `Serialize` and `Deserialize` implementations,
+    /// if they should be generated.
+    pub serde: Option<SerdeRepr>,
+    /// Whether to implement the `bytemuck` traits.
+    pub bytemuck: bool,
+    /// `mint` types to convert to and from, with their lengths.
+    pub mint: Vec<(syn::Ident, usize)>,
+    /// Whether to generate the `nalgebra` conversions and views.
+    pub nalgebra: bool,
+    /// Whether to generate the conversions to and from the `glam` types.
+    pub glam: bool,
+    /// Whether to implement the `approx` traits.
+    pub approx: bool,
+    /// Whether to implement the `num-traits` traits.
+    pub num_traits: bool,
+}
+
+/// Representation of a struct array with serde.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum SerdeRepr {
+    /// A tuple of the elements, e.g. `[1.0, 2.0]` in JSON.
+    Seq,
+    /// A struct with the named fields, e.g. `{"x": 1.0, "y": 2.0}` in JSON.
+    Map,
+}
+
+/// Options for generating swizzle accessors.
+#[derive(Clone,Debug,Default)]
+pub struct SwizzleOptions {
+    /// Maximum number of fields in a swizzle, if given. It must be given for
+    /// structs with more than `DEFAULT_SWIZZLE_MAX_LEN` fields.
+    pub max_len: Option<usize>,
+    /// Types returned by swizzles of the given numbers of fields, instead of
+    /// arrays.
+    pub targets: Vec<(usize, syn::Ty)>,
+}
+
+/// Maximum number of fields in a swizzle if `max_len` isn't given, which is
+/// also the maximum number of fields of a struct without `max_len`.
+pub const DEFAULT_SWIZZLE_MAX_LEN: usize = 4;
+
+/// Number of elements, made up of a known part and the lengths of array fields
+/// that are not integer literals.
+#[derive(Clone,Debug,Default)]
+pub struct Len<'a> {
+    pub known: usize,
+    pub exprs: Vec<&'a syn::ConstExpr>,
+}
+
+impl<'a> Len<'a> {
+    /// Adds the number of elements in a field, given its array length (or
+    /// `None` for a single element).
+    pub fn add(&mut self, array_len: Option<&'a syn::ConstExpr>) {
+        match array_len {
+            None => self.known += 1,
+            Some(&syn::ConstExpr::Lit(syn::Lit::Int(value, _))) => self.known += value as usize,
+            Some(expr) => self.exprs.push(expr),
+        }
+    }
+}
+
+impl<'a> ToTokens for Len<'a> {
+    fn to_tokens(&self, tokens: &mut ::quote::Tokens) {
+        let known = self.known;
+        if self.exprs.is_empty() {
+            known.to_tokens(tokens);
+        } else {
+            let exprs = &self.exprs;
+            tokens.append_all(&[quote!((#known #(+ (#exprs))*))]);
+        }
+    }
+}
+
+impl<'a> std::fmt::Display for Len<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.known)?;
+        for expr in &self.exprs {
+            let mut tokens = ::quote::Tokens::new();
+            expr.to_tokens(&mut tokens);
+            write!(f, " + {}", tokens)?;
+        }
+        Ok(())
+    }
+}
+
+/// Relevant information about a field of the struct.
+pub struct FieldInfo<'a> {
+    pub ident: Option<&'a syn::Ident>,
+    pub ty: &'a syn::Ty,
+    /// Length of the field if it is an array of elements, or `None` if it is a
+    /// single element.
+    pub array_len: Option<&'a syn::ConstExpr>,
+    /// Whether the field is a zero-sized field excluded from the array.
+    pub skip: bool,
+}
+
+/// A view of a contiguous range of fields as another struct array.
+pub struct View {
+    pub target: syn::Ident,
+    /// Range of the indices in `StructInfo::fields` of the viewed fields.
+    pub fields: Range<usize>,
+    /// The range as written in the option, for documentation and messages.
+    pub source: String,
+}
+
+/// Relevant information about the struct from the macro input.
+pub struct StructInfo<'a> {
+    pub name: &'a syn::Ident,
+    pub generics: &'a syn::Generics,
+    pub field_type: syn::Ty,
+    pub field_count: Len<'a>,
+    pub fields: Vec<FieldInfo<'a>>,
+    pub views: Vec<View>,
+    /// Shorter struct arrays whose fields are a prefix of this struct's
+    /// fields.
+    pub extends: Vec<View>,
+    /// `glam` types with the element type and number of elements of the
+    /// struct, if the `#[struct_array(glam)]` option is given.
+    pub glam: Vec<syn::Ident>,
+    pub options: StructOptions,
+}
+
+/// Returns the element type of the struct.
+///
+/// If all the fields have the same type, that is the element type. Otherwise,
+/// it is the type of the first field that is not an array, or the type of the
+/// elements of the first field if all the fields are arrays.
+fn element_type<'a>(fields: &[&'a syn::Field]) -> Option<&'a syn::Ty> {
+    let first = &fields.first()?.ty;
+    if fields.iter().all(|field| field.ty == *first) {
+        return Some(first);
+    }
+    fields.iter()
+        .map(|field| &field.ty)
+        .find(|ty| !matches!(**ty, syn::Ty::Array(..)))
+        .or(match *first {
+            syn::Ty::Array(ref elem, _) => Some(elem),
+            _ => None,
+        })
+}
+
+/// Returns the information about a field, or `None` if its type is neither the
+/// element type nor an array of the element type.
+fn field_info<'a>(field: &'a syn::Field, field_type: &syn::Ty) -> Option<FieldInfo<'a>> {
+    let array_len = if field.ty == *field_type {
+        None
+    } else {
+        match field.ty {
+            syn::Ty::Array(ref elem, ref len) if **elem == *field_type => Some(len),
+            _ => return None,
+        }
+    };
+    Some(FieldInfo {
+        ident: field.ident.as_ref(),
+        ty: &field.ty,
+        array_len,
+        skip: false,
+    })
+}
+
+/// Returns whether the type is `PhantomData`.
+fn is_phantom_data(ty: &syn::Ty) -> bool {
+    match *ty {
+        syn::Ty::Path(None, ref path) => {
+            path.segments.last().is_some_and(|segment| segment.ident == "PhantomData")
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether the field should be excluded from the array, either because
+/// it has the `#[struct_array(skip)]` attribute or because it is a
+/// `PhantomData`.
+fn is_skipped(field: &syn::Field) -> Result<bool, MacroInputError> {
+    let mut skip = is_phantom_data(&field.ty);
+    for item in struct_array_items(&field.attrs) {
+        match *item {
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref ident)) if ident == "skip" => {
+                skip = true;
+            }
+            // Used only by `#[derive(StructArrayGroups)]`.
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::NameValue(ref ident, _))
+                if ident == "group" => {}
+            _ => return Err(MacroInputError::UnknownOption(item_to_string(item))),
+        }
+    }
+    Ok(skip)
+}
+
+/// Returns the name of the group given with the `#[struct_array(group =
+/// "...")]` attribute on the field, if any.
+fn field_group(field: &syn::Field) -> Result<Option<String>, MacroInputError> {
+    let mut group = None;
+    for item in struct_array_items(&field.attrs) {
+        match *item {
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::NameValue(ref ident,
+                                                                   syn::Lit::Str(ref name, _)))
+                if ident == "group" => {
+                group = Some(name.clone());
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref ident)) if ident == "skip" => {}
+            _ => return Err(MacroInputError::UnknownOption(item_to_string(item))),
+        }
+    }
+    Ok(group)
+}
+
+/// Returns the items in all the `#[struct_array(...)]` attributes.
+fn struct_array_items(attrs: &[syn::Attribute]) -> Vec<&syn::NestedMetaItem> {
+    attrs.iter()
+        .filter_map(|attr| match attr.value {
+            syn::MetaItem::List(ref ident, ref items) if ident == "struct_array" => Some(items),
+            _ => None,
+        })
+        .flat_map(|items| items.iter())
+        .collect()
+}
+
+/// Returns the source of an attribute item, for error messages.
+fn item_to_string(item: &syn::NestedMetaItem) -> String {
+    let mut tokens = ::quote::Tokens::new();
+    item.to_tokens(&mut tokens);
+    tokens.to_string()
+}
+
+/// Parses the type in the string literal value of an option.
+fn parse_type_value(item: &syn::NestedMetaItem, value: &syn::Lit) -> Result<syn::Ty, MacroInputError> {
+    match *value {
+        syn::Lit::Str(ref ty, _) => syn::parse_type(ty).ok(),
+        _ => None,
+    }.ok_or_else(|| MacroInputError::InvalidOption(item_to_string(item)))
+}
+
+/// Returns the number of elements of a `mint` vector or point type, or `None`
+/// if there is no such type.
+fn mint_len(name: &str) -> Option<usize> {
+    match name {
+        "Vector2" | "Point2" => Some(2),
+        "Vector3" | "Point3" => Some(3),
+        "Vector4" => Some(4),
+        _ => None,
+    }
+}
+
+/// Returns the names of the `glam` types with the given element type and
+/// number of elements.
+///
+/// There are several types for three `f32` elements: `Vec3`, and `Vec3A`,
+/// which is aligned to 16 bytes.
+fn glam_types(field_type: &syn::Ty, field_count: &Len) -> Result<Vec<syn::Ident>, MacroInputError> {
+    let mut tokens = ::quote::Tokens::new();
+    field_type.to_tokens(&mut tokens);
+    let prefix = match tokens.as_str() {
+        "f32" => Some(""),
+        "f64" => Some("D"),
+        "i32" => Some("I"),
+        "u32" => Some("U"),
+        _ => None,
+    };
+    match prefix {
+        Some(prefix) if field_count.exprs.is_empty() && (2..=4).contains(&field_count.known) => {
+            let mut types = vec![syn::Ident::new(format!("{}Vec{}", prefix, field_count.known))];
+            if prefix.is_empty() && field_count.known == 3 {
+                types.push(syn::Ident::new("Vec3A"));
+            }
+            Ok(types)
+        }
+        _ => Err(MacroInputError::NoGlamType(format!("{} elements of type `{}`", field_count, tokens))),
+    }
+}
+
+/// Parses the items of a `swizzle(...)` option.
+fn parse_swizzle_options(items: &[syn::NestedMetaItem]) -> Result<SwizzleOptions, MacroInputError> {
+    let mut options = SwizzleOptions::default();
+    for item in items {
+        match *item {
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::NameValue(ref ident,
+                                                                   syn::Lit::Int(max_len, _)))
+                if ident == "max_len" => {
+                options.max_len = Some(max_len as usize);
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::NameValue(ref ident, ref value))
+                if ident.as_ref().starts_with("len") => {
+                let len = ident.as_ref()["len".len()..]
+                    .parse()
+                    .map_err(|_| MacroInputError::UnknownOption(item_to_string(item)))?;
+                options.targets.push((len, parse_type_value(item, value)?));
+            }
+            _ => return Err(MacroInputError::UnknownOption(item_to_string(item))),
+        }
+    }
+    Ok(options)
+}
+
+/// Parses the items of an option like `view(...)` that lists target types
+/// with ranges of fields.
+fn parse_range_options(items: &[syn::NestedMetaItem],
+                       views: &mut Vec<(syn::Ident, String)>)
+                      -> Result<(), MacroInputError> {
+    for item in items {
+        match *item {
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::NameValue(ref ident,
+                                                                   syn::Lit::Str(ref range, _)))
+                if views.iter().all(|(target, _)| target != ident) => {
+                views.push((ident.clone(), range.clone()));
+            }
+            _ => return Err(MacroInputError::InvalidOption(item_to_string(item))),
+        }
+    }
+    Ok(())
+}
+
+/// Returns the index of the element field with the given name (or index, for
+/// tuple structs).
+fn field_index(fields: &[FieldInfo], name: &str) -> Result<usize, MacroInputError> {
+    fields.iter()
+        .enumerate()
+        .position(|(index, field)| {
+            !field.skip &&
+            match field.ident {
+                Some(ident) => ident == name,
+                None => index.to_string() == name,
+            }
+        })
+        .ok_or_else(|| MacroInputError::UnknownField(name.to_string()))
+}
+
+/// Resolves a range of fields such as `x..=z`, `x..w` or `y..`.
+fn resolve_view(target: &syn::Ident, range: &str, fields: &[FieldInfo]) -> Result<View, MacroInputError> {
+    let invalid = || MacroInputError::InvalidOption(format!("{} = {:?}", target, range));
+    let (start, end, inclusive) = if let Some(split) = range.find("..=") {
+        (&range[..split], &range[split + 3..], true)
+    } else if let Some(split) = range.find("..") {
+        (&range[..split], &range[split + 2..], false)
+    } else {
+        return Err(invalid());
+    };
+    let (start, end) = (start.trim(), end.trim());
+    let start = if start.is_empty() {
+        0
+    } else {
+        field_index(fields, start)?
+    };
+    let end = if end.is_empty() {
+        if inclusive {
+            return Err(invalid());
+        }
+        fields.len()
+    } else {
+        field_index(fields, end)? + inclusive as usize
+    };
+    if start > end {
+        return Err(invalid());
+    }
+    Ok(View {
+        target: target.clone(),
+        fields: start..end,
+        source: range.to_string(),
+    })
+}
+
+/// Parses the options from the attributes on the struct.
+fn parse_options(ast: &syn::MacroInput) -> Result<StructOptions, MacroInputError> {
+    let mut options = StructOptions::default();
+    for item in struct_array_items(&ast.attrs) {
+        match *item {
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref ident)) if ident == "flatten" => {
+                options.flatten = true;
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::NameValue(ref ident, ref value))
+                if ident == "elem" => {
+                options.elem = Some(parse_type_value(item, value)?);
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref ident)) if ident == "swizzle" => {
+                options.swizzle = Some(SwizzleOptions::default());
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::List(ref ident, ref items))
+                if ident == "swizzle" => {
+                options.swizzle = Some(parse_swizzle_options(items)?);
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::List(ref ident, ref items))
+                if ident == "view" => {
+                parse_range_options(items, &mut options.views)?;
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::List(ref ident, ref items))
+                if ident == "extend" => {
+                parse_range_options(items, &mut options.extends)?;
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref ident)) if ident == "ops" => {
+                options.ops = true;
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref ident)) if ident == "bytemuck" => {
+                options.bytemuck = true;
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref ident)) if ident == "nalgebra" => {
+                options.nalgebra = true;
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref ident)) if ident == "glam" => {
+                options.glam = true;
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref ident)) if ident == "approx" => {
+                options.approx = true;
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref ident)) if ident == "num_traits" => {
+                options.num_traits = true;
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref ident)) if ident == "serde" => {
+                options.serde = Some(SerdeRepr::Seq);
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::NameValue(ref ident,
+                                                                   syn::Lit::Str(ref repr, _)))
+                if ident == "serde" => {
+                options.serde = Some(match repr.as_ref() {
+                    "seq" => SerdeRepr::Seq,
+                    "map" => SerdeRepr::Map,
+                    _ => return Err(MacroInputError::InvalidOption(item_to_string(item))),
+                });
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::List(ref ident, ref items))
+                if ident == "convert_to" => {
+                for item in items {
+                    match *item {
+                        syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref target)) => {
+                            options.convert_to.push(target.clone());
+                        }
+                        _ => return Err(MacroInputError::InvalidOption(item_to_string(item))),
+                    }
+                }
+            }
+            syn::NestedMetaItem::MetaItem(syn::MetaItem::List(ref ident, ref items))
+                if ident == "mint" => {
+                for item in items {
+                    match *item {
+                        syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref target)) => {
+                            let len = mint_len(target.as_ref()).ok_or_else(|| {
+                                MacroInputError::InvalidOption(item_to_string(item))
+                            })?;
+                            options.mint.push((target.clone(), len));
+                        }
+                        _ => return Err(MacroInputError::InvalidOption(item_to_string(item))),
+                    }
+                }
+            }
+            _ => return Err(MacroInputError::UnknownOption(item_to_string(item))),
+        }
+    }
+    if options.flatten && !ast.generics.ty_params.is_empty() {
+        return Err(MacroInputError::GenericOption("flatten".to_string()));
+    }
+    if !options.extends.is_empty() && !ast.generics.ty_params.is_empty() {
+        return Err(MacroInputError::GenericOption("extend(...)".to_string()));
+    }
+    if options.serde.is_some() && !cfg!(feature = "serde") {
+        return Err(MacroInputError::MissingFeature("serde".to_string()));
+    }
+    if options.bytemuck {
+        if !cfg!(feature = "bytemuck") {
+            return Err(MacroInputError::MissingFeature("bytemuck".to_string()));
+        }
+        if !ast.generics.ty_params.is_empty() {
+            return Err(MacroInputError::GenericOption("bytemuck".to_string()));
+        }
+    }
+    if !options.mint.is_empty() {
+        if !cfg!(feature = "mint") {
+            return Err(MacroInputError::MissingFeature("mint".to_string()));
+        }
+        if !ast.generics.ty_params.is_empty() {
+            return Err(MacroInputError::GenericOption("mint(...)".to_string()));
+        }
+    }
+    if options.nalgebra {
+        if !cfg!(feature = "nalgebra") {
+            return Err(MacroInputError::MissingFeature("nalgebra".to_string()));
+        }
+        if !ast.generics.ty_params.is_empty() {
+            return Err(MacroInputError::GenericOption("nalgebra".to_string()));
+        }
+    }
+    if options.glam && !cfg!(feature = "glam") {
+        return Err(MacroInputError::MissingFeature("glam".to_string()));
+    }
+    if options.approx && !cfg!(feature = "approx") {
+        return Err(MacroInputError::MissingFeature("approx".to_string()));
+    }
+    if options.num_traits && !cfg!(feature = "num-traits") {
+        return Err(MacroInputError::MissingFeature("num_traits".to_string()));
+    }
+    if !options.convert_to.is_empty() {
+        if !ast.generics.ty_params.is_empty() {
+            return Err(MacroInputError::GenericOption("convert_to(...)".to_string()));
+        }
+        match ast.body {
+            syn::Body::Struct(syn::VariantData::Struct(_)) => {}
+            _ => return Err(MacroInputError::UnnamedFields("convert_to(...)".to_string())),
+        }
+    }
+    Ok(options)
+}
+
+/// Extracts the relevant information from the macro input and checks that the
+/// struct meets the requirements for the macros.
+pub fn parse_input<'a>(ast: &'a syn::MacroInput) -> Result<StructInfo<'a>, MacroInputError> {
+    let repr_c =
+        syn::MetaItem::List("repr".into(),
+                            vec![syn::NestedMetaItem::MetaItem(syn::MetaItem::Word("C".into()))]);
+    if !ast.attrs.iter().any(|attr| attr.value == repr_c) {
+        Err(MacroInputError::NotReprC)
+    } else {
+        match ast.body {
+            syn::Body::Enum(_) => Err(MacroInputError::NotStruct),
+            syn::Body::Struct(ref data) => {
+                let skips = data.fields().iter().map(is_skipped).collect::<Result<Vec<_>, _>>()?;
+                let elements: Vec<_> = data.fields()
+                    .iter()
+                    .zip(&skips)
+                    .filter(|&(_, &skip)| !skip)
+                    .map(|(field, _)| field)
+                    .collect();
+                let options = parse_options(ast)?;
+                let field_type = match options.elem {
+                    Some(ref elem) => elem.clone(),
+                    None => element_type(&elements).ok_or(MacroInputError::ZeroFields)?.clone(),
+                };
+                if elements.iter().any(|field| field.vis != syn::Visibility::Public) {
+                    Err(MacroInputError::NonpublicField)
+                } else {
+                    let mut fields = Vec::new();
+                    let mut field_count = Len::default();
+                    for (field, &skip) in data.fields().iter().zip(&skips) {
+                        if skip {
+                            fields.push(FieldInfo {
+                                ident: field.ident.as_ref(),
+                                ty: &field.ty,
+                                array_len: None,
+                                skip: true,
+                            });
+                        } else {
+                            let info = field_info(field, &field_type)
+                                .ok_or(MacroInputError::DifferingFieldTypes)?;
+                            field_count.add(info.array_len);
+                            fields.push(info);
+                        }
+                    }
+                    let named_scalar_fields = fields.iter()
+                        .all(|field| field.skip || (field.ident.is_some() && field.array_len.is_none()));
+                    if options.swizzle.is_some() && !named_scalar_fields {
+                        return Err(MacroInputError::NamedScalarFields("swizzle".to_string()));
+                    }
+                    if let Some(SwizzleOptions { max_len: None, .. }) = options.swizzle {
+                        let count = fields.iter().filter(|field| !field.skip).count();
+                        if count > DEFAULT_SWIZZLE_MAX_LEN {
+                            return Err(MacroInputError::SwizzleMaxLen(count));
+                        }
+                    }
+                    if options.serde == Some(SerdeRepr::Map) && !named_scalar_fields {
+                        return Err(MacroInputError::NamedScalarFields("serde = \"map\"".to_string()));
+                    }
+                    let views = options.views
+                        .iter()
+                        .map(|(target, range)| resolve_view(target, range, &fields))
+                        .collect::<Result<_, _>>()?;
+                    let extends = options.extends
+                        .iter()
+                        .map(|(target, range)| {
+                            let view = resolve_view(target, range, &fields)?;
+                            // The range must be a prefix, with at least one element left.
+                            if fields[..view.fields.start].iter().any(|field| !field.skip) ||
+                               fields[view.fields.end..].iter().all(|field| field.skip) {
+                                return Err(MacroInputError::InvalidOption(format!("{} = {:?}", target, range)));
+                            }
+                            Ok(view)
+                        })
+                        .collect::<Result<_, _>>()?;
+                    let glam = if options.glam {
+                        glam_types(&field_type, &field_count)?
+                    } else {
+                        Vec::new()
+                    };
+                    Ok(StructInfo {
+                        name: &ast.ident,
+                        generics: &ast.generics,
+                        field_type,
+                        field_count,
+                        fields,
+                        views,
+                        extends,
+                        glam,
+                        options,
+                    })
+                }
+            }
+        }
+    }
+}
+
+/// Relevant information about a struct for `#[derive(StructArrayCopy)]`,
+/// whose fields may have differing types and whose layout doesn't matter.
+pub struct CopyStructInfo<'a> {
+    pub name: &'a syn::Ident,
+    pub generics: &'a syn::Generics,
+    /// The fields, with `array_len` always `None` since array fields are
+    /// copied as single values.
+    pub fields: Vec<FieldInfo<'a>>,
+}
+
+/// Extracts the relevant information from the macro input for
+/// `#[derive(StructArrayCopy)]`.
+pub fn parse_copy_input<'a>(ast: &'a syn::MacroInput) -> Result<CopyStructInfo<'a>, MacroInputError> {
+    let data = match ast.body {
+        syn::Body::Enum(_) => return Err(MacroInputError::NotStruct),
+        syn::Body::Struct(ref data) => data,
+    };
+    if let Some(item) = struct_array_items(&ast.attrs).first() {
+        return Err(MacroInputError::UnknownOption(item_to_string(item)));
+    }
+    let fields = data.fields()
+        .iter()
+        .map(|field| {
+            Ok(FieldInfo {
+                ident: field.ident.as_ref(),
+                ty: &field.ty,
+                array_len: None,
+                skip: is_skipped(field)?,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(CopyStructInfo {
+        name: &ast.ident,
+        generics: &ast.generics,
+        fields,
+    })
+}
+
+/// A contiguous run of fields of the same type, viewable as an array.
+pub struct Group<'a> {
+    pub name: String,
+    pub ty: &'a syn::Ty,
+    /// Names (or indices, for tuple structs) of the fields in order.
+    pub members: Vec<syn::Ident>,
+}
+
+/// Relevant information about a struct for `#[derive(StructArrayGroups)]`.
+pub struct GroupStructInfo<'a> {
+    pub name: &'a syn::Ident,
+    pub generics: &'a syn::Generics,
+    pub groups: Vec<Group<'a>>,
+}
+
+/// Returns whether the attribute is `#[repr(...)]` with `packed` or
+/// `packed(N)` among its hints.
+fn is_repr_packed(attr: &syn::Attribute) -> bool {
+    match attr.value {
+        syn::MetaItem::List(ref name, ref hints) if name == "repr" => {
+            hints.iter().any(|hint| match *hint {
+                syn::NestedMetaItem::MetaItem(ref hint) => hint.name() == "packed",
+                syn::NestedMetaItem::Literal(_) => false,
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Extracts the relevant information from the macro input for
+/// `#[derive(StructArrayGroups)]` and checks that the fields of each group are
+/// contiguous, public, and of the same type.
+pub fn parse_group_input<'a>(ast: &'a syn::MacroInput) -> Result<GroupStructInfo<'a>, MacroInputError> {
+    // The fields of a packed struct may not be aligned, so neither would the
+    // arrays of the groups.
+    if ast.attrs.iter().any(is_repr_packed) {
+        return Err(MacroInputError::Packed);
+    }
+    let repr_c =
+        syn::MetaItem::List("repr".into(),
+                            vec![syn::NestedMetaItem::MetaItem(syn::MetaItem::Word("C".into()))]);
+    if !ast.attrs.iter().any(|attr| attr.value == repr_c) {
+        return Err(MacroInputError::NotReprC);
+    }
+    let data = match ast.body {
+        syn::Body::Enum(_) => return Err(MacroInputError::NotStruct),
+        syn::Body::Struct(ref data) => data,
+    };
+    let mut groups: Vec<Group> = Vec::new();
+    let mut prev_group = None;
+    for (index, field) in data.fields().iter().enumerate() {
+        let group = field_group(field)?;
+        if let Some(ref name) = group {
+            if field.vis != syn::Visibility::Public {
+                return Err(MacroInputError::NonpublicField);
+            }
+            let member = match field.ident {
+                Some(ref ident) => ident.clone(),
+                None => syn::Ident::new(index),
+            };
+            if prev_group.as_ref() == Some(name) {
+                let last = groups.last_mut().unwrap();
+                if *last.ty != field.ty {
+                    return Err(MacroInputError::DifferingFieldTypes);
+                }
+                last.members.push(member);
+            } else if groups.iter().any(|group| group.name == *name) {
+                return Err(MacroInputError::NoncontiguousGroup(name.clone()));
+            } else {
+                groups.push(Group {
+                    name: name.clone(),
+                    ty: &field.ty,
+                    members: vec![member],
+                });
+            }
+        }
+        prev_group = group;
+    }
+    Ok(GroupStructInfo {
+        name: &ast.ident,
+        generics: &ast.generics,
+        groups,
+    })
+}
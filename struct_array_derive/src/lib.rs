@@ -0,0 +1,154 @@
+//! Derive macros for the [`struct_array`](https://docs.rs/struct_array)
+//! crate.
+//!
+//! This crate is an implementation detail of `struct_array`, which re-exports
+//! the macros alongside the `StructArray` trait. Depend on `struct_array`
+//! instead of using this crate directly.
+
+#![recursion_limit = "500"]
+
+extern crate proc_macro;
+use proc_macro::TokenStream;
+
+extern crate syn;
+
+#[macro_use]
+extern crate quote;
+use quote::ToTokens;
+
+mod approx;
+mod bytemuck;
+mod codegen;
+mod copy;
+mod glam;
+mod groups;
+mod input;
+mod mint;
+mod nalgebra;
+mod num_traits;
+mod ops;
+mod serde;
+mod swizzle;
+
+use codegen::{impl_struct_array_convert, impl_struct_array_deref,
+              impl_struct_array_trait_and_options};
+use copy::impl_struct_array_copy;
+use groups::impl_struct_array_groups;
+use input::{parse_copy_input, parse_group_input, parse_input};
+
+/// Implements derive of `StructArray`.
+///
+/// This function is called by the Rust compiler when compiling code that uses
+/// `#[derive(StructArray)]`.
+#[proc_macro_derive(StructArray, attributes(struct_array))]
+pub fn derive_struct_array(input: TokenStream) -> TokenStream {
+    let source = input.to_string();
+
+    // Parse the string representation into a syntax tree.
+    let ast = syn::parse_macro_input(&source).unwrap();
+
+    // Check the struct and get the necessary info.
+    let struct_info = parse_input(&ast).unwrap_or_else(|err| {
+        panic!("Error expanding #[derive(StructArray)]: {}", err)
+    });
+
+    // Build the output.
+    let mut expanded = impl_struct_array_trait_and_options(&struct_info);
+    impl_struct_array_deref(&struct_info).to_tokens(&mut expanded);
+    impl_struct_array_convert(&struct_info).to_tokens(&mut expanded);
+
+    // Return the generated impl as a TokenStream.
+    expanded.parse().unwrap()
+}
+
+/// Implements derive of `StructArrayDeref`.
+///
+/// This function is called by the Rust compiler when compiling code that uses
+/// `#[derive(StructArrayDeref)]`.
+#[proc_macro_derive(StructArrayDeref, attributes(struct_array))]
+pub fn derive_struct_array_deref(input: TokenStream) -> TokenStream {
+    let source = input.to_string();
+
+    // Parse the string representation into a syntax tree.
+    let ast = syn::parse_macro_input(&source).unwrap();
+
+    // Check the struct and get the necessary info.
+    let struct_info = parse_input(&ast).unwrap_or_else(|err| {
+        panic!("Error expanding #[derive(StructArrayDeref)]: {}", err)
+    });
+
+    // Build the output.
+    let expanded = impl_struct_array_deref(&struct_info);
+
+    // Return the generated impl as a TokenStream.
+    expanded.parse().unwrap()
+}
+
+/// Implements derive of `StructArrayConvert`.
+///
+/// This function is called by the Rust compiler when compiling code that uses
+/// `#[derive(StructArrayConvert)]`.
+#[proc_macro_derive(StructArrayConvert, attributes(struct_array))]
+pub fn derive_struct_array_convert(input: TokenStream) -> TokenStream {
+    let source = input.to_string();
+
+    // Parse the string representation into a syntax tree.
+    let ast = syn::parse_macro_input(&source).unwrap();
+
+    // Check the struct and get the necessary info.
+    let struct_info = parse_input(&ast).unwrap_or_else(|err| {
+        panic!("Error expanding #[derive(StructArrayConvert)]: {}", err)
+    });
+
+    // Build the output.
+    let expanded = impl_struct_array_convert(&struct_info);
+
+    // Return the generated impl as a TokenStream.
+    expanded.parse().unwrap()
+}
+
+/// Implements derive of `StructArrayCopy`.
+///
+/// This function is called by the Rust compiler when compiling code that uses
+/// `#[derive(StructArrayCopy)]`.
+#[proc_macro_derive(StructArrayCopy, attributes(struct_array))]
+pub fn derive_struct_array_copy(input: TokenStream) -> TokenStream {
+    let source = input.to_string();
+
+    // Parse the string representation into a syntax tree.
+    let ast = syn::parse_macro_input(&source).unwrap();
+
+    // Check the struct and get the necessary info.
+    let struct_info = parse_copy_input(&ast).unwrap_or_else(|err| {
+        panic!("Error expanding #[derive(StructArrayCopy)]: {}", err)
+    });
+
+    // Build the output.
+    let expanded = impl_struct_array_copy(&struct_info);
+
+    // Return the generated impl as a TokenStream.
+    expanded.parse().unwrap()
+}
+
+/// Implements derive of `StructArrayGroups`.
+///
+/// This function is called by the Rust compiler when compiling code that uses
+/// `#[derive(StructArrayGroups)]`.
+#[proc_macro_derive(StructArrayGroups, attributes(struct_array))]
+pub fn derive_struct_array_groups(input: TokenStream) -> TokenStream {
+    let source = input.to_string();
+
+    // Parse the string representation into a syntax tree.
+    let ast = syn::parse_macro_input(&source).unwrap();
+
+    // Check the struct and get the necessary info.
+    let struct_info = parse_group_input(&ast).unwrap_or_else(|err| {
+        panic!("Error expanding #[derive(StructArrayGroups)]: {}", err)
+    });
+
+    // Build the output.
+    let expanded = impl_struct_array_groups(&struct_info);
+
+    // Return the generated impl as a TokenStream.
+    expanded.parse().unwrap()
+}
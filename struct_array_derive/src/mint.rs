@@ -0,0 +1,36 @@
+//! Generation of the `mint` conversions for the `#[struct_array(mint(...))]`
+//! option.
+
+use quote;
+
+use input::StructInfo;
+
+/// Implements `From` in both directions between the struct and each of the
+/// `mint` types in the `#[struct_array(mint(...))]` option.
+///
+/// The conversions go through the arrays, which `mint` converts to and from,
+/// and the number of elements is checked at compile time.
+pub fn impl_mint(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, ref field_type, ref options, .. } = *struct_info;
+    let mint = quote!(::struct_array::__private::mint);
+    let impls = options.mint.iter().map(|&(ref target, len)| {
+        let message = format!("`{}` must have {} elements to convert to and from `mint::{}`",
+                              name, len, target);
+        quote! {
+            const _: () = assert!(<#name as ::struct_array::StructArray>::LEN == #len, #message);
+
+            impl ::std::convert::From<#name> for #mint::#target<#field_type> {
+                fn from(value: #name) -> #mint::#target<#field_type> {
+                    ::std::convert::From::from(::struct_array::StructArray::into_array(value))
+                }
+            }
+
+            impl ::std::convert::From<#mint::#target<#field_type>> for #name {
+                fn from(value: #mint::#target<#field_type>) -> #name {
+                    ::struct_array::StructArray::from_array(::std::convert::From::from(value))
+                }
+            }
+        }
+    });
+    quote!(#(#impls)*)
+}
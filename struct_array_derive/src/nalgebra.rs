@@ -0,0 +1,66 @@
+//! Generation of the `nalgebra` conversions and views for the
+//! `#[struct_array(nalgebra)]` option.
+
+use quote;
+use syn;
+
+use input::StructInfo;
+
+/// Implements `From` in both directions between the struct and `SVector` and
+/// `Point`, and generates methods that view the struct as a column vector and
+/// a slice of structs as a matrix with a column for each struct, if enabled by
+/// the `#[struct_array(nalgebra)]` option.
+pub fn impl_nalgebra(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, ref field_type, ref field_count, ref options, .. } = *struct_info;
+    if !options.nalgebra {
+        return quote!();
+    }
+    let nalgebra = quote!(::struct_array::__private::nalgebra);
+    let conversions = ["SVector", "Point"].iter().map(|target| {
+        let target = syn::Ident::new(*target);
+        let target = quote!(#nalgebra::#target<#field_type, { #field_count }>);
+        quote! {
+            impl ::std::convert::From<#name> for #target {
+                fn from(value: #name) -> #target {
+                    ::std::convert::From::from(::struct_array::StructArray::into_array(value))
+                }
+            }
+
+            impl ::std::convert::From<#target> for #name {
+                fn from(value: #target) -> #name {
+                    ::struct_array::StructArray::from_array(::std::convert::From::from(value))
+                }
+            }
+        }
+    });
+    quote! {
+        #(#conversions)*
+
+        impl #name {
+            /// Views the struct as a column vector.
+            pub fn as_vector_view(&self) -> #nalgebra::SVectorView<'_, #field_type, { #field_count }> {
+                #nalgebra::SVectorView::from_slice(::struct_array::StructArray::as_slice(self))
+            }
+
+            /// Views the struct mutably as a column vector.
+            pub fn as_vector_view_mut(&mut self) -> #nalgebra::SVectorViewMut<'_, #field_type, { #field_count }> {
+                #nalgebra::SVectorViewMut::from_slice(::struct_array::StructArray::as_mut_slice(self))
+            }
+
+            /// Views a slice of structs as a matrix with a column for each
+            /// struct.
+            pub fn as_matrix_view(slice: &[#name]) -> #nalgebra::DMatrixView<'_, #field_type> {
+                let flat = ::struct_array::StructArray::as_flat_slice(slice);
+                #nalgebra::DMatrixView::from_slice(flat, #field_count, slice.len())
+            }
+
+            /// Views a slice of structs mutably as a matrix with a column for
+            /// each struct.
+            pub fn as_matrix_view_mut(slice: &mut [#name]) -> #nalgebra::DMatrixViewMut<'_, #field_type> {
+                let columns = slice.len();
+                let flat = ::struct_array::StructArray::as_flat_slice_mut(slice);
+                #nalgebra::DMatrixViewMut::from_slice(flat, #field_count, columns)
+            }
+        }
+    }
+}
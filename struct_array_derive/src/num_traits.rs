@@ -0,0 +1,153 @@
+//! Generation of the `num-traits` trait implementations for the
+//! `#[struct_array(num_traits)]` option.
+
+use quote;
+use quote::ToTokens;
+use std::mem;
+use syn;
+use syn::fold::{self, Folder};
+
+use input::StructInfo;
+
+/// Implements `Bounded` element-wise, and `Zero` and `One` if the
+/// `#[struct_array(ops)]` option provides the `Add` and `Mul` implementations
+/// they require, if enabled by the `#[struct_array(num_traits)]` option.
+///
+/// For a struct with a single type parameter that is the element type, also
+/// generates `num_cast::<U>()`, which converts each element with `NumCast`.
+pub fn impl_num_traits(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, generics, ref field_type, ref options, .. } = *struct_info;
+    if !options.num_traits {
+        return quote!();
+    }
+    let num_traits = quote!(::struct_array::__private::num_traits);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = &generics.where_clause.predicates;
+    let elementwise = |value: quote::Tokens| {
+        quote! {
+            ::struct_array::StructArray::from_array(::std::array::from_fn(|_| #value))
+        }
+    };
+    let min_value = elementwise(quote!(<#field_type as #num_traits::Bounded>::min_value()));
+    let max_value = elementwise(quote!(<#field_type as #num_traits::Bounded>::max_value()));
+    let zero_one = if options.ops {
+        let zero = elementwise(quote!(<#field_type as #num_traits::Zero>::zero()));
+        let one = elementwise(quote!(<#field_type as #num_traits::One>::one()));
+        quote! {
+            impl #impl_generics #num_traits::Zero for #name #ty_generics
+                where #(#predicates,)* #field_type: #num_traits::Zero
+            {
+                fn zero() -> Self {
+                    #zero
+                }
+
+                fn is_zero(&self) -> bool {
+                    ::struct_array::StructArray::iter(self).all(#num_traits::Zero::is_zero)
+                }
+            }
+
+            impl #impl_generics #num_traits::One for #name #ty_generics
+                where #(#predicates,)* #field_type: #num_traits::One
+            {
+                fn one() -> Self {
+                    #one
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+    let num_cast = impl_num_cast(struct_info);
+    quote! {
+        #zero_one
+
+        impl #impl_generics #num_traits::Bounded for #name #ty_generics
+            where #(#predicates,)* #field_type: #num_traits::Bounded
+        {
+            fn min_value() -> Self {
+                #min_value
+            }
+
+            fn max_value() -> Self {
+                #max_value
+            }
+        }
+
+        #num_cast
+    }
+}
+
+/// Replaces a type parameter with another one in the paths it folds, which
+/// include the types and the trait bounds in a `where` clause.
+struct ReplaceParam<'a> {
+    from: &'a syn::Ident,
+    to: &'a syn::Ident,
+}
+
+impl<'a> Folder for ReplaceParam<'a> {
+    fn fold_path(&mut self, path: syn::Path) -> syn::Path {
+        let mut path = fold::noop_fold_path(self, path);
+        // Both `T` and `T::Assoc` start with the parameter.
+        if !path.global && path.segments[0].ident == *self.from {
+            path.segments[0].ident = self.to.clone();
+        }
+        path
+    }
+}
+
+/// Generates `num_cast::<U>()` if the struct has a single type parameter,
+/// which is the element type.
+fn impl_num_cast(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, generics, ref field_type, ref field_count, .. } = *struct_info;
+    if generics.ty_params.len() != 1 || !generics.lifetimes.is_empty() {
+        return quote!();
+    }
+    let param = &generics.ty_params[0].ident;
+    let mut elem = quote::Tokens::new();
+    field_type.to_tokens(&mut elem);
+    if elem.as_str() != param.as_ref() {
+        return quote!();
+    }
+    let num_traits = quote!(::struct_array::__private::num_traits);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let target = syn::Ident::new(if param == "U" { "V" } else { "U" });
+    // The struct's bounds on its parameter must also hold for the target type,
+    // so move them into the `where` clause and replace the parameter there.
+    let mut target_generics = generics.clone();
+    let bounds = mem::take(&mut target_generics.ty_params[0].bounds);
+    if !bounds.is_empty() {
+        target_generics.where_clause.predicates.push(syn::WherePredicate::BoundPredicate(
+            syn::WhereBoundPredicate {
+                bound_lifetimes: Vec::new(),
+                bounded_ty: syn::Ty::Path(None, param.clone().into()),
+                bounds,
+            }));
+    }
+    let target_predicates = ReplaceParam { from: param, to: &target }
+        .fold_generics(target_generics)
+        .where_clause
+        .predicates;
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Converts each element to another numeric type with `NumCast`.
+            ///
+            /// Returns `None` if any of the elements can't be represented in
+            /// the other type.
+            pub fn num_cast<#target>(self) -> ::std::option::Option<#name<#target>>
+                where #param: #num_traits::ToPrimitive,
+                      #target: #num_traits::NumCast,
+                      #(#target_predicates,)*
+                      #name<#target>: ::struct_array::StructArray<Array = [#target; #field_count]>
+            {
+                let converted = ::struct_array::StructArray::into_array(self)
+                    .map(<#target as #num_traits::NumCast>::from);
+                if converted.iter().all(::std::option::Option::is_some) {
+                    let converted = converted.map(::std::option::Option::unwrap);
+                    ::std::option::Option::Some(::struct_array::StructArray::from_array(converted))
+                } else {
+                    ::std::option::Option::None
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Generation of the element-wise operators for the `#[struct_array(ops)]`
+//! option.
+
+use quote;
+use syn;
+
+use input::StructInfo;
+
+/// Implements `Add`, `Sub`, `Mul` and `Div` element-wise, if enabled by the
+/// `#[struct_array(ops)]` option.
+pub fn impl_ops(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, generics, ref field_type, ref options, .. } = *struct_info;
+    if !options.ops {
+        return quote!();
+    }
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let predicates = &generics.where_clause.predicates;
+    let ops = [("Add", "add"), ("Sub", "sub"), ("Mul", "mul"), ("Div", "div")];
+    let impls = ops.iter().map(|&(op, method)| {
+        let (op, method) = (syn::Ident::new(op), syn::Ident::new(method));
+        let op_doc = format!("Applies `{}` to each pair of elements.", op);
+        quote! {
+            impl #impl_generics ::std::ops::#op for #name #ty_generics
+                where #(#predicates,)* #field_type: ::std::ops::#op<Output = #field_type>
+            {
+                type Output = Self;
+
+                #[doc = #op_doc]
+                fn #method(self, rhs: Self) -> Self {
+                    let mut rhs = ::std::iter::IntoIterator::into_iter(::struct_array::StructArray::into_array(rhs));
+                    let lhs = ::struct_array::StructArray::into_array(self);
+                    ::struct_array::StructArray::from_array(lhs.map(|lhs| {
+                        ::std::ops::#op::#method(lhs, rhs.next().unwrap())
+                    }))
+                }
+            }
+        }
+    });
+    quote! {
+        #(#impls)*
+    }
+}
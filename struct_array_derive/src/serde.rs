@@ -0,0 +1,65 @@
+//! Generation of the `Serialize` and `Deserialize` implementations for the
+//! `#[struct_array(serde)]` option.
+//!
+//! The implementations delegate to generic functions in the runtime crate,
+//! which is compiled with serde support when its `serde` feature is enabled.
+
+use quote;
+use syn;
+
+use input::{SerdeRepr, StructInfo};
+
+/// Implements `Serialize` and `Deserialize`, if enabled by the
+/// `#[struct_array(serde)]` option.
+pub fn impl_serde(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, generics, ref field_type, ref fields, ref options, .. } = *struct_info;
+    let repr = match options.serde {
+        Some(repr) => repr,
+        None => return quote!(),
+    };
+    let serde = quote!(::struct_array::__private::serde);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let mut generics_de = generics.clone();
+    generics_de.lifetimes.insert(0, syn::LifetimeDef::new("'de"));
+    let (impl_generics_de, _, _) = generics_de.split_for_impl();
+    let predicates = &generics.where_clause.predicates;
+    let predicates_de = predicates;
+    let name_str = name.to_string();
+    let (serialize, deserialize) = match repr {
+        SerdeRepr::Seq => {
+            (quote!(#serde::serialize_seq(self, serializer)),
+             quote!(#serde::deserialize_seq(deserializer)))
+        }
+        SerdeRepr::Map => {
+            let names: Vec<String> = fields.iter()
+                .filter(|field| !field.skip)
+                .filter_map(|field| field.ident)
+                .map(|ident| ident.to_string())
+                .collect();
+            let names_de = names.clone();
+            (quote!(#serde::serialize_map(self, #name_str, &[#(#names),*], serializer)),
+             quote!(#serde::deserialize_map(deserializer, #name_str, &[#(#names_de),*])))
+        }
+    };
+    quote! {
+        impl #impl_generics #serde::Serialize for #name #ty_generics
+            where #(#predicates,)* #field_type: #serde::Serialize
+        {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+                where S: #serde::Serializer
+            {
+                #serialize
+            }
+        }
+
+        impl #impl_generics_de #serde::Deserialize<'de> for #name #ty_generics
+            where #(#predicates_de,)* #field_type: #serde::Deserialize<'de>
+        {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+                where D: #serde::Deserializer<'de>
+            {
+                #deserialize
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Generation of the swizzle accessors.
+//!
+//! A swizzle is a combination of fields, e.g. `zyx` on a struct with fields
+//! `x`, `y` and `z`. Getters are generated for every combination of two or
+//! more fields, up to the configured maximum length, and setters for the
+//! combinations that don't repeat a field. Getters whose names would be
+//! keywords are skipped, as are swizzles whose names are ambiguous.
+
+use std::collections::HashMap;
+
+use quote;
+use syn;
+
+use input::{StructInfo, SwizzleOptions, DEFAULT_SWIZZLE_MAX_LEN};
+
+/// Strict and reserved keywords of all editions, which can't be used as the
+/// names of getters.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Returns every sequence of `len` indices less than `count`.
+fn index_sequences(count: usize, len: usize) -> Vec<Vec<usize>> {
+    let mut sequences = vec![Vec::new()];
+    for _ in 0..len {
+        sequences = sequences
+            .into_iter()
+            .flat_map(|sequence| {
+                (0..count).map(move |index| {
+                    let mut sequence = sequence.clone();
+                    sequence.push(index);
+                    sequence
+                })
+            })
+            .collect();
+    }
+    sequences
+}
+
+/// Implements the swizzle getters and setters, if enabled by the
+/// `#[struct_array(swizzle)]` option.
+pub fn impl_swizzles(struct_info: &StructInfo) -> quote::Tokens {
+    let StructInfo { name, generics, ref field_type, ref fields, ref options, .. } = *struct_info;
+    let SwizzleOptions { max_len, ref targets } = match options.swizzle {
+        Some(ref swizzle) => swizzle.clone(),
+        None => return quote!(),
+    };
+    let max_len = max_len.unwrap_or(DEFAULT_SWIZZLE_MAX_LEN);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let idents: Vec<&syn::Ident> = fields
+        .iter()
+        .filter(|field| !field.skip)
+        .filter_map(|field| field.ident)
+        .collect();
+    // Single-letter names like `x` and `r` are simply concatenated, as is
+    // usual for swizzles, but longer names are separated by underscores.
+    let separator = if idents.iter().all(|ident| ident.as_ref().len() == 1) { "" } else { "_" };
+    let swizzle_name = |sequence: &[usize]| {
+        sequence.iter().map(|&index| idents[index].as_ref()).collect::<Vec<_>>().join(separator)
+    };
+    // Names with underscores can be joined in more than one way, e.g. `a_b_c`
+    // from `a` and `b_c` or from `a_b` and `c`, so such names are skipped.
+    let mut name_counts = HashMap::new();
+    for len in 2..(max_len + 1) {
+        for sequence in index_sequences(idents.len(), len) {
+            *name_counts.entry(swizzle_name(&sequence)).or_insert(0) += 1;
+        }
+    }
+    let mut methods = Vec::new();
+    for len in 2..(max_len + 1) {
+        let target = targets.iter().rev().find(|&&(target_len, _)| target_len == len);
+        for sequence in index_sequences(idents.len(), len) {
+            let swizzle = swizzle_name(&sequence);
+            if name_counts[&swizzle] > 1 {
+                continue;
+            }
+            let members: Vec<&syn::Ident> = sequence.iter().map(|&index| idents[index]).collect();
+            let names: Vec<&str> = members.iter().map(|member| member.as_ref()).collect();
+            let getter = syn::Ident::new(swizzle.clone());
+            let doc = format!("Returns the `{}` fields.", names.join("`, `"));
+            let getter_members = members.clone();
+            // A getter can't be named after a keyword, but its setter can.
+            if !KEYWORDS.contains(&swizzle.as_str()) {
+                methods.push(match target {
+                    Some((_, target)) => quote! {
+                        #[doc=#doc]
+                        pub fn #getter(&self) -> #target
+                            where #field_type: ::std::clone::Clone
+                        {
+                            ::std::convert::From::from([#(self.#getter_members.clone()),*])
+                        }
+                    },
+                    None => quote! {
+                        #[doc=#doc]
+                        pub fn #getter(&self) -> [#field_type; #len]
+                            where #field_type: ::std::clone::Clone
+                        {
+                            [#(self.#getter_members.clone()),*]
+                        }
+                    },
+                });
+            }
+
+            let mut distinct = sequence.clone();
+            distinct.sort();
+            distinct.dedup();
+            if distinct.len() == len {
+                let setter = syn::Ident::new(format!("set_{}", swizzle));
+                let doc = format!("Sets the `{}` fields.", names.join("`, `"));
+                let values: Vec<syn::Ident> =
+                    (0..len).map(|index| syn::Ident::new(format!("v{}", index))).collect();
+                let values2 = values.clone();
+                methods.push(quote! {
+                    #[doc=#doc]
+                    pub fn #setter<V>(&mut self, value: V)
+                        where V: ::std::convert::Into<[#field_type; #len]>
+                    {
+                        let [#(#values),*] = value.into();
+                        #(self.#members = #values2;)*
+                    }
+                });
+            }
+        }
+    }
+    if methods.is_empty() {
+        return quote!();
+    }
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#methods)*
+        }
+    }
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "approx")]
+
+#[macro_use]
+extern crate approx;
+#[macro_use]
+extern crate struct_array;
+
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(approx)]
+struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Generic struct with an array field.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(approx)]
+struct Segment<T> {
+    pub start: [T; 2],
+    pub end: [T; 2],
+    pub width: T,
+}
+
+#[test]
+fn test_assert_macros() {
+    let a = Vec3 { x: 0.1 + 0.2, y: 1., z: -2. };
+    let b = Vec3 { x: 0.3, y: 1., z: -2. };
+    assert_abs_diff_eq!(a, b);
+    assert_relative_eq!(a, b);
+    assert_ulps_eq!(a, b);
+    assert_relative_ne!(a, Vec3 { x: 0.3, y: 1.001, z: -2. });
+}
+
+#[test]
+fn test_generic() {
+    let a = Segment { start: [0.1f64 + 0.2, 0.], end: [1., 1e10], width: 1. };
+    let b = Segment { start: [0.3, 0.], end: [1., 1e10 + 1e-3], width: 1. };
+    assert_relative_eq!(a, b, max_relative = 1e-12);
+    assert_relative_ne!(a, b);
+    assert!(a.abs_diff_ne(&b, f64::EPSILON));
+    assert!(a.relative_eq(&b, f64::EPSILON, 1e-12));
+    assert!(!a.ulps_eq(&b, f64::EPSILON, 1));
+}
+
+#[test]
+fn test_epsilon() {
+    let a = Vec3 { x: 1., y: 2., z: 3. };
+    let b = Vec3 { x: 1.05, y: 2., z: 2.95 };
+    assert_eq!(Vec3::default_epsilon(), f32::EPSILON);
+    assert!(a.abs_diff_eq(&b, 0.1));
+    assert!(a.abs_diff_ne(&b, 0.01));
+    assert_abs_diff_eq!(a, b, epsilon = 0.1);
+}
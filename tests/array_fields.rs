@@ -0,0 +1,117 @@
+#[macro_use]
+extern crate struct_array;
+
+use struct_array::StructArray;
+
+/// Struct array with a field that is an array of elements.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Vertex {
+    pub xyz: [f32; 3],
+    pub w: f32,
+}
+
+const N: usize = 2;
+
+/// Struct array with array fields whose length is not a literal.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Channels {
+    pub gain: f32,
+    pub left: [f32; N],
+    pub right: [f32; N],
+}
+
+/// Tuple struct array with an array field.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Rgba(pub [u8; 3], pub u8);
+
+/// Struct array whose fields all have the same array type, so the arrays are
+/// the elements.
+#[derive(Clone,Debug,PartialEq,StructArrayConvert)]
+#[repr(C)]
+struct Segment {
+    pub start: [i32; 2],
+    pub end: [i32; 2],
+}
+
+#[test]
+fn test_len() {
+    assert_eq!(Vertex::LEN, 4);
+    assert_eq!(Channels::LEN, 5);
+    assert_eq!(Rgba::LEN, 4);
+}
+
+#[test]
+fn test_index_consts() {
+    assert_eq!(Vertex::XYZ_INDEX, 0);
+    assert_eq!(Vertex::W_INDEX, 3);
+    assert_eq!(Channels::GAIN_INDEX, 0);
+    assert_eq!(Channels::LEFT_INDEX, 1);
+    assert_eq!(Channels::RIGHT_INDEX, 3);
+}
+
+#[test]
+fn test_deref() {
+    let vertex = Vertex { xyz: [1., 2., 3.], w: 4. };
+    assert_eq!(*vertex, [1., 2., 3., 4.]);
+    assert_eq!(vertex[Vertex::W_INDEX], 4.);
+}
+
+#[test]
+fn test_deref_mut() {
+    let mut channels = Channels { gain: 1., left: [2., 3.], right: [4., 5.] };
+    channels[Channels::RIGHT_INDEX + 1] = 6.;
+    assert_eq!(channels.right, [4., 6.]);
+}
+
+#[test]
+fn test_from_array() {
+    let rgba: Rgba = [1, 2, 3, 4].into();
+    assert_eq!(rgba, Rgba([1, 2, 3], 4));
+}
+
+#[test]
+fn test_into_array() {
+    let array: [f32; 4] = Vertex { xyz: [1., 2., 3.], w: 4. }.into();
+    assert_eq!(array, [1., 2., 3., 4.]);
+}
+
+#[test]
+fn test_from_slice_ref() {
+    let slice = &[1., 2., 3., 4., 5.][..];
+    let channels: &Channels = slice.into();
+    assert_eq!(channels, &Channels { gain: 1., left: [2., 3.], right: [4., 5.] });
+}
+
+#[test]
+#[should_panic]
+fn test_from_slice_ref_wrong_len() {
+    let slice = &[1., 2., 3., 4.][..];
+    let _: &Channels = slice.into();
+}
+
+#[test]
+fn test_identical_array_fields() {
+    let array: [[i32; 2]; 2] = Segment { start: [0, 1], end: [2, 3] }.into();
+    assert_eq!(array, [[0, 1], [2, 3]]);
+}
+
+/// Struct array whose fields all have the same array type, with the element
+/// type given explicitly.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(elem = "i32")]
+struct Mat2 {
+    pub row0: [i32; 2],
+    pub row1: [i32; 2],
+}
+
+#[test]
+fn test_explicit_elem_with_identical_array_fields() {
+    assert_eq!(Mat2::LEN, 4);
+    assert_eq!(Mat2::ROW1_INDEX, 2);
+    let mat: Mat2 = [1, 2, 3, 4].into();
+    assert_eq!(mat, Mat2 { row0: [1, 2], row1: [3, 4] });
+}
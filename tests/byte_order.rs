@@ -0,0 +1,51 @@
+extern crate struct_array;
+
+use struct_array::StructArray;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Ports {
+    pub src: u16,
+    pub dst: u16,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[test]
+fn test_swap_bytes() {
+    let ports = Ports { src: 0x1234, dst: 0xabcd };
+    assert_eq!(ports.swap_bytes(), Ports { src: 0x3412, dst: 0xcdab });
+    let p = Point { x: 1.5, y: -2. };
+    assert_eq!(p.swap_bytes().x.to_bits(), 1.5f64.to_bits().swap_bytes());
+    assert_eq!(p.swap_bytes().swap_bytes(), p);
+}
+
+#[test]
+fn test_to_from_le_be() {
+    let ports = Ports { src: 0x1234, dst: 80 };
+    assert_eq!(ports.to_be(), Ports { src: 0x1234u16.to_be(), dst: 80u16.to_be() });
+    assert_eq!(ports.to_le(), Ports { src: 0x1234u16.to_le(), dst: 80u16.to_le() });
+    assert_eq!(Ports::from_be(ports.to_be()), ports);
+    assert_eq!(Ports::from_le(ports.to_le()), ports);
+}
+
+#[test]
+fn test_slices_in_place() {
+    let bytes = [0x12, 0x34, 0x00, 0x50, 0xab, 0xcd, 0x01, 0xbb];
+    let mut ports = [Ports { src: 0, dst: 0 }; 2];
+    for (port, chunk) in Ports::as_flat_slice_mut(&mut ports).iter_mut().zip(bytes.chunks(2)) {
+        *port = u16::from_ne_bytes([chunk[0], chunk[1]]);
+    }
+    Ports::from_be_slice(&mut ports);
+    assert_eq!(ports, [Ports { src: 0x1234, dst: 80 }, Ports { src: 0xabcd, dst: 443 }]);
+    Ports::to_le_slice(&mut ports);
+    Ports::from_le_slice(&mut ports);
+    Ports::swap_bytes_slice(&mut ports);
+    Ports::to_be_slice(&mut ports);
+    assert_eq!(ports[0], Ports { src: 0x1234, dst: 80 }.swap_bytes().to_be());
+}
@@ -0,0 +1,51 @@
+extern crate struct_array;
+
+use struct_array::{ByteViewError, StructArray};
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Sample {
+    pub left: i16,
+    pub right: i16,
+}
+
+/// Buffer aligned for the element types used here.
+#[repr(C, align(8))]
+struct Buffer([u8; 9]);
+
+#[test]
+fn test_as_ne_bytes() {
+    let s = Sample { left: 1, right: -1 };
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&1i16.to_ne_bytes());
+    expected.extend_from_slice(&(-1i16).to_ne_bytes());
+    assert_eq!(s.as_ne_bytes(), &expected[..]);
+}
+
+#[test]
+fn test_try_from_bytes() {
+    let mut buf = Buffer([0; 9]);
+    buf.0[..4].copy_from_slice(Sample { left: 2, right: 3 }.as_ne_bytes());
+    assert_eq!(Sample::try_from_bytes(&buf.0[..4]), Ok(&Sample { left: 2, right: 3 }));
+    assert_eq!(Sample::try_from_bytes(&buf.0[..3]), Err(ByteViewError::Length));
+    assert_eq!(Sample::try_from_bytes(&buf.0[..8]), Err(ByteViewError::Length));
+}
+
+#[test]
+fn test_try_slice_from_bytes() {
+    let mut buf = Buffer([0; 9]);
+    buf.0[4..8].copy_from_slice(Sample { left: 4, right: 5 }.as_ne_bytes());
+    let samples = Sample::try_slice_from_bytes(&buf.0[..8]).unwrap();
+    assert_eq!(samples, [Sample { left: 0, right: 0 }, Sample { left: 4, right: 5 }]);
+    assert_eq!(Sample::try_slice_from_bytes(&buf.0[..0]), Ok(&[][..]));
+    assert_eq!(Sample::try_slice_from_bytes(&buf.0[..6]), Err(ByteViewError::Length));
+}
+
+#[test]
+fn test_misaligned() {
+    let buf = Buffer([0; 9]);
+    assert_eq!(Sample::try_from_bytes(&buf.0[1..5]), Err(ByteViewError::Alignment));
+    assert_eq!(Sample::try_slice_from_bytes(&buf.0[1..9]), Err(ByteViewError::Alignment));
+    assert_eq!(ByteViewError::Alignment.to_string(),
+               "the bytes are not aligned for the struct array");
+}
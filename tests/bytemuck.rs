@@ -0,0 +1,53 @@
+#![cfg(feature = "bytemuck")]
+
+#[macro_use]
+extern crate struct_array;
+extern crate bytemuck;
+
+use std::marker::PhantomData;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(bytemuck)]
+struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+struct Meters;
+
+/// Struct array with an array field and a `PhantomData` field.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(bytemuck)]
+struct Segment {
+    pub start: [u16; 2],
+    pub end: [u16; 2],
+    pub length: u16,
+    pub unit: PhantomData<Meters>,
+}
+
+#[test]
+fn test_cast_slice() {
+    let vs = [Vec3 { x: 1., y: 2., z: 3. }, Vec3 { x: 4., y: 5., z: 6. }];
+    let floats: &[f32] = bytemuck::cast_slice(&vs);
+    assert_eq!(floats, [1., 2., 3., 4., 5., 6.]);
+    let back: &[Vec3] = bytemuck::cast_slice(floats);
+    assert_eq!(back, vs);
+}
+
+#[test]
+fn test_bytes_of() {
+    let segment = Segment { start: [1, 2], end: [3, 4], length: 5, unit: PhantomData };
+    let bytes = bytemuck::bytes_of(&segment);
+    assert_eq!(bytes.len(), 10);
+    assert_eq!(bytemuck::pod_read_unaligned::<Segment>(bytes), segment);
+}
+
+#[test]
+fn test_zeroed() {
+    let v: Vec3 = bytemuck::Zeroable::zeroed();
+    assert_eq!(v, Vec3 { x: 0., y: 0., z: 0. });
+}
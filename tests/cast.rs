@@ -0,0 +1,80 @@
+extern crate struct_array;
+
+use struct_array::StructArray;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Rgb {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+/// Same shape as `Vec3`, with an array field.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Normal(pub [f32; 2], pub f32);
+
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Names {
+    pub first: String,
+    pub last: String,
+}
+
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Pair(pub String, pub String);
+
+#[test]
+fn test_cast() {
+    let v = Vec3 { x: 1., y: 2., z: 3. };
+    assert_eq!(v.cast::<Rgb>(), Rgb { r: 1., g: 2., b: 3. });
+    let n: Normal = v.cast();
+    assert_eq!(n, Normal([1., 2.], 3.));
+}
+
+#[test]
+fn test_cast_owned_elements() {
+    let names = Names { first: "a".to_string(), last: "b".to_string() };
+    let pair: Pair = names.cast();
+    assert_eq!(pair, Pair("a".to_string(), "b".to_string()));
+}
+
+#[test]
+fn test_cast_ref() {
+    let v = Vec3 { x: 1., y: 2., z: 3. };
+    assert_eq!(v.cast_ref::<Rgb>().g, 2.);
+}
+
+#[test]
+fn test_cast_mut() {
+    let mut v = Vec3 { x: 1., y: 2., z: 3. };
+    v.cast_mut::<Rgb>().b = 4.;
+    assert_eq!(v.z, 4.);
+}
+
+#[test]
+fn test_cast_slice() {
+    let vs = [Vec3 { x: 1., y: 2., z: 3. }, Vec3 { x: 4., y: 5., z: 6. }];
+    let colors: &[Rgb] = Vec3::cast_slice(&vs);
+    assert_eq!(colors.len(), 2);
+    assert_eq!(colors[1], Rgb { r: 4., g: 5., b: 6. });
+}
+
+#[test]
+fn test_cast_slice_mut() {
+    let mut vs = vec![Vec3 { x: 1., y: 2., z: 3. }; 2];
+    for normal in Vec3::cast_slice_mut::<Normal>(&mut vs) {
+        normal.0[0] = 0.;
+    }
+    assert!(vs.iter().all(|v| v.x == 0.));
+}
@@ -0,0 +1,73 @@
+extern crate struct_array;
+
+use std::marker::PhantomData;
+use struct_array::StructArray;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(convert_to(Bgr))]
+struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(convert_to(Rgb))]
+struct Bgr {
+    pub b: u8,
+    pub g: u8,
+    pub r: u8,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+struct Linear;
+
+/// Conversion with a skipped field, which is matched by name too.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(convert_to(Argb))]
+struct Rgba {
+    pub r: String,
+    pub g: String,
+    pub b: String,
+    pub a: String,
+    pub space: PhantomData<Linear>,
+}
+
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Argb {
+    pub a: String,
+    pub r: String,
+    pub g: String,
+    pub b: String,
+    pub space: PhantomData<Linear>,
+}
+
+#[test]
+fn test_from() {
+    let bgr: Bgr = Rgb { r: 1, g: 2, b: 3 }.into();
+    assert_eq!(bgr, Bgr { b: 3, g: 2, r: 1 });
+    assert_eq!(*bgr, [3, 2, 1]);
+    assert_eq!(Rgb::from(bgr), Rgb { r: 1, g: 2, b: 3 });
+}
+
+#[test]
+fn test_convert_slice_in_place() {
+    let mut pixels = vec![Rgb { r: 1, g: 2, b: 3 }, Rgb { r: 4, g: 5, b: 6 }];
+    {
+        let bgr: &mut [Bgr] = Rgb::convert_slice_in_place(&mut pixels);
+        assert_eq!(bgr[1], Bgr { b: 6, g: 5, r: 4 });
+    }
+    assert_eq!(Rgb::as_flat_slice(&pixels), &[3, 2, 1, 6, 5, 4]);
+}
+
+#[test]
+fn test_convert_slice_in_place_owned_elements() {
+    let s = |s: &str| s.to_string();
+    let mut colors = [Rgba { r: s("r"), g: s("g"), b: s("b"), a: s("a"), space: PhantomData }];
+    let argb = Rgba::convert_slice_in_place::<Argb>(&mut colors);
+    assert_eq!(*argb[0], [s("a"), s("r"), s("g"), s("b")]);
+}
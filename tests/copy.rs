@@ -0,0 +1,37 @@
+#[macro_use]
+extern crate struct_array;
+
+use std::marker::PhantomData;
+
+/// Struct with fields of differing types and no `#[repr(C)]`.
+#[derive(Clone,Debug,PartialEq,StructArrayCopy)]
+struct Telemetry {
+    pub voltage: u16,
+    pub temperature: i32,
+    pub speed: f32,
+}
+
+/// Tuple struct with a skipped field, which is filled in with its default.
+#[derive(Clone,Debug,PartialEq,StructArrayCopy)]
+struct Sample<T>(u8, PhantomData<T>, i16);
+
+#[test]
+fn test_to_array() {
+    let t = Telemetry { voltage: 5, temperature: -20, speed: 1.5 };
+    let array: [f64; 3] = t.to_array();
+    assert_eq!(array, [5., -20., 1.5]);
+}
+
+#[test]
+fn test_from_array() {
+    let sample = Sample::<()>::from_array([1i64, -2]);
+    assert_eq!(sample, Ok(Sample(1, PhantomData, -2)));
+    assert_eq!(sample.unwrap().to_array::<i64>(), [1, -2]);
+}
+
+#[test]
+fn test_from_array_out_of_range() {
+    let err = Sample::<()>::from_array([1i64, 40000]).unwrap_err();
+    assert_eq!(err.field(), "2");
+    assert_eq!(err.to_string(), "the value for field `2` could not be converted to its type");
+}
@@ -0,0 +1,63 @@
+extern crate struct_array;
+
+use struct_array::StructArray;
+
+/// Example generic struct array.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Example<T> {
+    /// x member
+    pub x: T,
+    /// y member
+    pub y: T,
+}
+
+#[test]
+fn test_len() {
+    assert_eq!(<Example<u8> as StructArray>::LEN, 2);
+}
+
+#[test]
+fn test_deref() {
+    let example = Example { x: 0u32, y: 1 };
+    assert_eq!(*example, [0, 1]);
+}
+
+#[test]
+fn test_into_array() {
+    let example = Example { x: String::from("a"), y: String::from("b") };
+    let array: [String; 2] = example.into();
+    assert_eq!(array, [String::from("a"), String::from("b")]);
+}
+
+#[test]
+fn test_from_array() {
+    let array = [String::from("a"), String::from("b")];
+    let example: Example<String> = array.into();
+    assert_eq!(example, Example { x: String::from("a"), y: String::from("b") });
+}
+
+#[test]
+fn test_from_array_ref_mut() {
+    let array = &mut [0., 1.];
+    {
+        let example: &mut Example<f64> = array.into();
+        example.y = 2.;
+    }
+    assert_eq!(array, &mut [0., 2.]);
+}
+
+#[test]
+fn test_from_slice_ref() {
+    let array = [0, 1];
+    let slice: &[i16] = &array;
+    let example: &Example<i16> = slice.into();
+    assert_eq!(example, &Example { x: 0, y: 1 });
+}
+
+#[test]
+fn test_struct_ref_as_slice_ref() {
+    let example = Example { x: 0, y: 1 };
+    let slice: &[i16] = example.as_ref();
+    assert_eq!(slice, [0, 1]);
+}
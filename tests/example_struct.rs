@@ -1,3 +1,5 @@
+#![allow(unused_mut, clippy::needless_borrow)]
+
 #[macro_use]
 extern crate struct_array;
 
@@ -0,0 +1,83 @@
+#[macro_use]
+extern crate struct_array;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(extend(Vec2 = "x..=y"))]
+struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Extends `Point` by two fields, which are passed as an array.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(extend(Point = "..w"))]
+struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Rgb(pub String, pub String, pub String);
+
+/// Extends a tuple struct of non-`Copy` elements.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(extend(Rgb = "..3"))]
+struct Rgba(pub String, pub String, pub String, pub String);
+
+#[test]
+fn test_extend() {
+    let v = Vec2 { x: 1., y: 2. };
+    assert_eq!(v.extend(3.), Vec3 { x: 1., y: 2., z: 3. });
+}
+
+#[test]
+fn test_truncate() {
+    let v = Vec3 { x: 1., y: 2., z: 3. };
+    assert_eq!(v.truncate(), Vec2 { x: 1., y: 2. });
+}
+
+#[test]
+fn test_concat_and_split() {
+    let v: Vec3 = (Vec2 { x: 1., y: 2. }, 3.).into();
+    assert_eq!(v, Vec3 { x: 1., y: 2., z: 3. });
+    let (xy, z): (Vec2, f32) = v.into();
+    assert_eq!(xy, Vec2 { x: 1., y: 2. });
+    assert_eq!(z, 3.);
+}
+
+#[test]
+fn test_array_rest() {
+    let rect = Point { x: 1., y: 2. }.extend([3., 4.]);
+    assert_eq!(rect, Rect { x: 1., y: 2., w: 3., h: 4. });
+    let (_, size): (Point, [f32; 2]) = rect.into();
+    assert_eq!(size, [3., 4.]);
+}
+
+#[test]
+fn test_non_copy_elements() {
+    let rgb = Rgb("r".to_string(), "g".to_string(), "b".to_string());
+    let rgba = rgb.clone().extend("a".to_string());
+    assert_eq!(rgba.3, "a");
+    assert_eq!(rgba.truncate(), rgb);
+}
@@ -0,0 +1,89 @@
+extern crate struct_array;
+
+use struct_array::{FlatStructArray, StructArray};
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Struct array of struct arrays, viewable as a flat array of `f32`.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(flatten)]
+struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+/// Two levels of nesting.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(flatten)]
+struct AabbPair(pub Aabb, pub Aabb);
+
+fn aabb() -> Aabb {
+    Aabb {
+        min: Vec3 { x: 0., y: 1., z: 2. },
+        max: Vec3 { x: 3., y: 4., z: 5. },
+    }
+}
+
+#[test]
+fn test_lens() {
+    assert_eq!(Vec3::FLAT_LEN, 3);
+    assert_eq!(Aabb::LEN, 2);
+    assert_eq!(Aabb::FLAT_LEN, 6);
+    assert_eq!(AabbPair::LEN, 2);
+    assert_eq!(AabbPair::FLAT_LEN, 12);
+}
+
+#[test]
+fn test_deref_unflattened() {
+    let aabb = aabb();
+    assert_eq!(aabb[1], Vec3 { x: 3., y: 4., z: 5. });
+}
+
+#[test]
+fn test_into_flat_array() {
+    let array: [f32; 6] = aabb().into();
+    assert_eq!(array, [0., 1., 2., 3., 4., 5.]);
+}
+
+#[test]
+fn test_from_flat_array() {
+    let aabb: Aabb = [0., 1., 2., 3., 4., 5.].into();
+    assert_eq!(aabb, self::aabb());
+}
+
+#[test]
+fn test_as_flat_array_mut() {
+    let mut aabb = aabb();
+    {
+        let array: &mut [f32; 6] = aabb.as_mut();
+        array[4] = 10.;
+    }
+    assert_eq!(aabb.max.y, 10.);
+}
+
+#[test]
+fn test_as_scalar_slice() {
+    let aabb = aabb();
+    let slice: &[f32] = (&aabb).into();
+    assert_eq!(slice, [0., 1., 2., 3., 4., 5.]);
+}
+
+#[test]
+fn test_nested_scalar_slice_mut() {
+    let mut pair = AabbPair(aabb(), aabb());
+    {
+        let slice: &mut [f32] = pair.as_mut();
+        assert_eq!(slice.len(), 12);
+        slice[6] = -1.;
+    }
+    assert_eq!(pair.1.min.x, -1.);
+    assert_eq!(pair.as_scalar_slice()[6], -1.);
+}
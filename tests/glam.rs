@@ -0,0 +1,79 @@
+#![cfg(feature = "glam")]
+
+#[macro_use]
+extern crate struct_array;
+extern crate glam;
+
+use glam::{DVec4, IVec2, UVec3, Vec3, Vec3A};
+use std::marker::PhantomData;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(glam)]
+struct Normal {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Tuple struct of `i32`.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(glam)]
+struct Offset(pub i32, pub i32);
+
+/// Struct with an array field.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(glam)]
+struct Extent {
+    pub size: [u32; 2],
+    pub layers: u32,
+}
+
+/// Generic struct whose type parameter is only used by a zero-sized field.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(glam)]
+struct Color<Space> {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub a: f64,
+    pub space: PhantomData<Space>,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+struct Srgb;
+
+#[test]
+fn test_vec3() {
+    let n = Normal { x: 0., y: 1., z: 0. };
+    let v: Vec3 = n.into();
+    assert_eq!(v, Vec3::Y);
+    assert_eq!(Normal::from(v.cross(Vec3::Z)), Normal { x: 1., y: 0., z: 0. });
+}
+
+#[test]
+fn test_vec3a() {
+    let v: Vec3A = Normal { x: 1., y: 2., z: 3. }.into();
+    assert_eq!(v, Vec3A::new(1., 2., 3.));
+    assert_eq!(Normal::from(v * 2.), Normal { x: 2., y: 4., z: 6. });
+}
+
+#[test]
+fn test_integer_vectors() {
+    assert_eq!(IVec2::from(Offset(-1, 2)), IVec2::new(-1, 2));
+    assert_eq!(Offset::from(IVec2::ONE), Offset(1, 1));
+    let e = Extent { size: [640, 480], layers: 6 };
+    assert_eq!(UVec3::from(e), UVec3::new(640, 480, 6));
+    assert_eq!(Extent::from(UVec3::new(1, 2, 3)), Extent { size: [1, 2], layers: 3 });
+}
+
+#[test]
+fn test_generic() {
+    let c = Color::<Srgb> { r: 1., g: 0.5, b: 0.25, a: 1., space: PhantomData };
+    let v = DVec4::from(c);
+    assert_eq!(v, DVec4::new(1., 0.5, 0.25, 1.));
+    assert_eq!(Color::<Srgb>::from(v), c);
+}
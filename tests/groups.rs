@@ -0,0 +1,64 @@
+#[macro_use]
+extern crate struct_array;
+
+/// Packet header with a run of coordinates among other fields.
+#[derive(Clone,Debug,PartialEq,StructArrayGroups)]
+#[repr(C)]
+struct Header {
+    pub id: u32,
+    #[struct_array(group = "coords")]
+    pub coords_x: f32,
+    #[struct_array(group = "coords")]
+    pub coords_y: f32,
+    #[struct_array(group = "coords")]
+    pub coords_z: f32,
+    pub flags: u8,
+    #[struct_array(group = "extra")]
+    pub extra_a: u16,
+    #[struct_array(group = "extra")]
+    pub extra_b: u16,
+}
+
+/// Generic tuple struct with a group.
+#[derive(StructArrayGroups)]
+#[repr(C)]
+struct Tagged<T>(pub u8,
+                 #[struct_array(group = "values")] pub T,
+                 #[struct_array(group = "values")] pub T);
+
+fn header() -> Header {
+    Header {
+        id: 7,
+        coords_x: 1.,
+        coords_y: 2.,
+        coords_z: 3.,
+        flags: 0,
+        extra_a: 4,
+        extra_b: 5,
+    }
+}
+
+#[test]
+fn test_group() {
+    let h = header();
+    assert_eq!(h.coords(), &[1., 2., 3.]);
+    assert_eq!(h.extra(), &[4, 5]);
+}
+
+#[test]
+fn test_group_mut() {
+    let mut h = header();
+    h.coords_mut()[2] = 6.;
+    h.extra_mut().reverse();
+    assert_eq!(h.coords_z, 6.);
+    assert_eq!((h.extra_a, h.extra_b), (5, 4));
+    assert_eq!(h.id, 7);
+}
+
+#[test]
+fn test_generic_group() {
+    let mut t = Tagged(1, 2u64, 3u64);
+    t.values_mut()[0] = 4;
+    assert_eq!(t.values(), &[4, 3]);
+    assert_eq!(t.0, 1);
+}
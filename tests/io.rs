@@ -0,0 +1,70 @@
+extern crate struct_array;
+
+use std::io::{Cursor, ErrorKind};
+use struct_array::StructArray;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Header {
+    pub magic: u16,
+    pub version: u16,
+    pub lens: [u16; 2],
+}
+
+#[derive(Clone,Copy,Debug,Default,PartialEq,StructArray)]
+#[repr(C)]
+struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[test]
+fn test_read_le_be() {
+    let bytes = [1, 2, 3, 4, 5, 6, 7, 8];
+    let le = Header::read_le(&bytes[..]).unwrap();
+    assert_eq!(le, Header { magic: 0x0201, version: 0x0403, lens: [0x0605, 0x0807] });
+    let be = Header::read_be(&bytes[..]).unwrap();
+    assert_eq!(be, Header { magic: 0x0102, version: 0x0304, lens: [0x0506, 0x0708] });
+}
+
+#[test]
+fn test_write_le_be() {
+    let header = Header { magic: 0x0102, version: 0x0304, lens: [0x0506, 0x0708] };
+    let mut le = Vec::new();
+    header.write_le(&mut le).unwrap();
+    assert_eq!(le, [2, 1, 4, 3, 6, 5, 8, 7]);
+    let mut be = Vec::new();
+    header.write_be(&mut be).unwrap();
+    assert_eq!(be, [1, 2, 3, 4, 5, 6, 7, 8]);
+}
+
+#[test]
+fn test_short_read() {
+    let err = Header::read_le(&[1, 2, 3][..]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_slice_round_trip() {
+    let points = [Point { x: 1., y: -2. }, Point { x: 0.5, y: 1e10 }];
+    let mut bytes = Vec::new();
+    Point::write_slice_be(&points, &mut bytes).unwrap();
+    assert_eq!(&bytes[..4], &1f32.to_be_bytes());
+    let mut read = [Point::default(); 2];
+    Point::read_slice_into_be(&mut read, Cursor::new(&bytes)).unwrap();
+    assert_eq!(read, points);
+
+    bytes.clear();
+    Point::write_slice_le(&points, &mut bytes).unwrap();
+    assert_eq!(&bytes[4..8], &(-2f32).to_le_bytes());
+    let mut read = [Point::default(); 2];
+    Point::read_slice_into_le(&mut read, &bytes[..]).unwrap();
+    assert_eq!(read, points);
+}
+
+#[test]
+fn test_slice_short_read() {
+    let mut points = [Point::default(); 2];
+    let err = Point::read_slice_into_le(&mut points, &[0; 12][..]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
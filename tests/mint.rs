@@ -0,0 +1,61 @@
+#![cfg(feature = "mint")]
+
+#[macro_use]
+extern crate struct_array;
+extern crate mint;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(mint(Vector3, Point3))]
+struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Tuple struct of integers.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(mint(Vector2, Point2))]
+struct Size(pub u32, pub u32);
+
+/// Struct with an array field, which counts as several elements.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(mint(Vector4))]
+struct Quat {
+    pub v: [f64; 3],
+    pub s: f64,
+}
+
+/// Stands in for a math library function that accepts mint types.
+fn length<V: Into<mint::Vector3<f32>>>(v: V) -> f32 {
+    let v = v.into();
+    (v.x * v.x + v.y * v.y + v.z * v.z).sqrt()
+}
+
+#[test]
+fn test_vector() {
+    let v = Vec3 { x: 1., y: 2., z: 3. };
+    let m: mint::Vector3<f32> = v.into();
+    assert_eq!(m, mint::Vector3 { x: 1., y: 2., z: 3. });
+    assert_eq!(Vec3::from(m), v);
+    assert_eq!(length(Vec3 { x: 0., y: 3., z: 4. }), 5.);
+}
+
+#[test]
+fn test_point() {
+    let p: mint::Point3<f32> = Vec3 { x: 4., y: 5., z: 6. }.into();
+    assert_eq!(p, mint::Point3 { x: 4., y: 5., z: 6. });
+    let s = Size::from(mint::Point2 { x: 640, y: 480 });
+    assert_eq!(s, Size(640, 480));
+    assert_eq!(mint::Vector2::from(s), mint::Vector2 { x: 640, y: 480 });
+}
+
+#[test]
+fn test_array_field() {
+    let q = Quat { v: [1., 2., 3.], s: 4. };
+    let m: mint::Vector4<f64> = q.into();
+    assert_eq!(m, mint::Vector4 { x: 1., y: 2., z: 3., w: 4. });
+    assert_eq!(Quat::from(m), q);
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "nalgebra")]
+
+#[macro_use]
+extern crate struct_array;
+extern crate nalgebra;
+
+use nalgebra::{Matrix2x3, Point3, Vector3};
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(nalgebra)]
+struct Position {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// Tuple struct of integers.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(nalgebra)]
+struct Cell(pub i32, pub i32);
+
+#[test]
+fn test_vector() {
+    let p = Position { x: 1., y: 2., z: 3. };
+    let v: Vector3<f64> = p.into();
+    assert_eq!(v, Vector3::new(1., 2., 3.));
+    assert_eq!(Position::from(v * 2.), Position { x: 2., y: 4., z: 6. });
+}
+
+#[test]
+fn test_point() {
+    let p: Point3<f64> = Position { x: 1., y: 2., z: 3. }.into();
+    assert_eq!(p, Point3::new(1., 2., 3.));
+    assert_eq!(Position::from(p + Vector3::x()), Position { x: 2., y: 2., z: 3. });
+}
+
+#[test]
+fn test_vector_view() {
+    let mut p = Position { x: 3., y: 0., z: 4. };
+    assert_eq!(p.as_vector_view().norm(), 5.);
+    p.as_vector_view_mut().scale_mut(2.);
+    assert_eq!(p, Position { x: 6., y: 0., z: 8. });
+}
+
+#[test]
+fn test_matrix_view() {
+    let mut cells = [Cell(1, 2), Cell(3, 4), Cell(5, 6)];
+    {
+        let m = Cell::as_matrix_view(&cells);
+        assert_eq!(m.shape(), (2, 3));
+        assert_eq!(m, Matrix2x3::new(1, 3, 5, 2, 4, 6));
+    }
+    Cell::as_matrix_view_mut(&mut cells).row_mut(1).fill(0);
+    assert_eq!(cells, [Cell(1, 0), Cell(3, 0), Cell(5, 0)]);
+    assert_eq!(Cell::as_matrix_view(&[]).shape(), (2, 0));
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "ndarray")]
+
+extern crate ndarray;
+extern crate struct_array;
+
+use ndarray::{array, s, Array2, Axis, ErrorKind};
+use struct_array::StructArray;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Sample {
+    pub time: f64,
+    pub value: f64,
+    pub weight: f64,
+}
+
+fn samples() -> Vec<Sample> {
+    vec![Sample { time: 0., value: 1., weight: 0.5 },
+         Sample { time: 1., value: 3., weight: 1.5 }]
+}
+
+#[test]
+fn test_as_ndarray_view() {
+    let mut sample = samples()[1];
+    assert_eq!(sample.as_ndarray_view(), array![1., 3., 1.5]);
+    sample.as_ndarray_view_mut()[2] = 2.;
+    assert_eq!(sample.weight, 2.);
+}
+
+#[test]
+fn test_slice_as_ndarray_view() {
+    let mut samples = samples();
+    {
+        let view = Sample::slice_as_ndarray_view(&samples);
+        assert_eq!(view, array![[0., 1., 0.5], [1., 3., 1.5]]);
+        assert_eq!(view.sum_axis(Axis(0)), array![1., 4., 2.]);
+    }
+    Sample::slice_as_ndarray_view_mut(&mut samples).column_mut(1).fill(0.);
+    assert_eq!(samples[1], Sample { time: 1., value: 0., weight: 1.5 });
+    assert_eq!(Sample::slice_as_ndarray_view(&[]).dim(), (0, 3));
+}
+
+#[test]
+fn test_slice_from_ndarray_view() {
+    let mut array = Array2::from_shape_vec((2, 3), vec![0., 1., 0.5, 1., 3., 1.5]).unwrap();
+    assert_eq!(Sample::slice_from_ndarray_view(array.view()).unwrap(), &samples()[..]);
+    Sample::slice_from_ndarray_view_mut(array.view_mut()).unwrap()[0].time = -1.;
+    assert_eq!(array[[0, 0]], -1.);
+}
+
+#[test]
+fn test_slice_from_ndarray_view_errors() {
+    let array = Array2::<f64>::zeros((3, 4));
+    let err = Sample::slice_from_ndarray_view(array.view()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::IncompatibleShape);
+    // Every other row is not contiguous.
+    let err = Sample::slice_from_ndarray_view(array.slice(s![..;2, ..3])).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::IncompatibleLayout);
+    // Column-major order is not accepted either.
+    let mut transposed = Array2::<f64>::zeros((3, 2));
+    let err = Sample::slice_from_ndarray_view_mut(transposed.view_mut().reversed_axes())
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::IncompatibleLayout);
+}
@@ -0,0 +1,100 @@
+#![cfg(feature = "num-traits")]
+
+#[macro_use]
+extern crate struct_array;
+extern crate num_traits;
+
+use num_traits::{Bounded, One, Zero};
+use std::marker::PhantomData;
+use std::ops::Add;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(num_traits, ops)]
+struct Vec3<T: Copy> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/// Non-generic tuple struct with an array field.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(num_traits, ops)]
+struct Range(pub [u8; 2], pub u8);
+
+/// Generic struct whose type parameter is not the element type, so it has no
+/// `num_cast`.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(num_traits)]
+struct Tagged<Tag> {
+    pub a: i16,
+    pub b: i16,
+    pub tag: PhantomData<Tag>,
+}
+
+/// Struct with bounds on its parameter, which `num_cast` must also require of
+/// the target type.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(num_traits)]
+struct Interval<T: Copy + PartialOrd>
+    where T: Default
+{
+    pub start: T,
+    pub end: T,
+}
+
+/// Struct with its own `Add`, which `#[struct_array(num_traits)]` doesn't
+/// conflict with.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(num_traits)]
+struct Angles(pub u16, pub u16);
+
+impl Add for Angles {
+    type Output = Angles;
+
+    fn add(self, rhs: Angles) -> Angles {
+        Angles((self.0 + rhs.0) % 360, (self.1 + rhs.1) % 360)
+    }
+}
+
+/// Stands in for generic numeric code.
+fn sum<T: Zero + Copy>(values: &[T]) -> T {
+    values.iter().fold(T::zero(), |acc, &v| acc + v)
+}
+
+#[test]
+fn test_zero_one() {
+    assert_eq!(Vec3::<f32>::zero(), Vec3 { x: 0., y: 0., z: 0. });
+    assert!(Vec3::<f32>::zero().is_zero());
+    assert!(!Vec3 { x: 0., y: 1., z: 0. }.is_zero());
+    assert_eq!(Range::one(), Range([1, 1], 1));
+    assert!(Range::one().is_one());
+    let v = Vec3 { x: 2, y: -3, z: 4 };
+    assert_eq!(v * Vec3::one(), v);
+    assert_eq!(v + Vec3::zero(), v);
+    assert_eq!(sum(&[v, v, Vec3 { x: 1, y: 1, z: 1 }]), Vec3 { x: 5, y: -5, z: 9 });
+}
+
+#[test]
+fn test_bounded() {
+    assert_eq!(Range::min_value(), Range([0, 0], 0));
+    assert_eq!(Range::max_value(), Range([255, 255], 255));
+    let max = Tagged::<()>::max_value();
+    assert_eq!((max.a, max.b), (i16::MAX, i16::MAX));
+    assert_eq!(Angles::min_value() + Angles(10, 370), Angles(10, 10));
+}
+
+#[test]
+fn test_num_cast() {
+    let v = Vec3 { x: 1.5f64, y: -2., z: 300. };
+    assert_eq!(v.num_cast::<i32>(), Some(Vec3 { x: 1, y: -2, z: 300 }));
+    assert_eq!(v.num_cast::<u8>(), None);
+    assert_eq!(Vec3 { x: 1u8, y: 2, z: 3 }.num_cast::<f32>(), Some(Vec3 { x: 1., y: 2., z: 3. }));
+    let interval = Interval { start: -1i32, end: 2 };
+    assert_eq!(interval.num_cast::<f64>(), Some(Interval { start: -1., end: 2. }));
+    assert_eq!(interval.num_cast::<u32>(), None);
+}
@@ -0,0 +1,28 @@
+#[macro_use]
+extern crate struct_array;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(ops)]
+struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// Non-generic tuple struct with an array field.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(ops)]
+struct Range(pub [u8; 2], pub u8);
+
+#[test]
+fn test_ops() {
+    let a = Vec2 { x: 6, y: 8 };
+    let b = Vec2 { x: 3, y: 2 };
+    assert_eq!(a + b, Vec2 { x: 9, y: 10 });
+    assert_eq!(a - b, Vec2 { x: 3, y: 6 });
+    assert_eq!(a * b, Vec2 { x: 18, y: 16 });
+    assert_eq!(a / b, Vec2 { x: 2, y: 4 });
+    assert_eq!(Vec2 { x: 0.5, y: 1. } * Vec2 { x: 2., y: 3. }, Vec2 { x: 1., y: 3. });
+    assert_eq!(Range([1, 2], 3) + Range([1, 1], 1), Range([2, 3], 4));
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "serde")]
+
+#[macro_use]
+extern crate struct_array;
+extern crate serde_json;
+extern crate serde_test;
+
+use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+use std::marker::PhantomData;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(serde)]
+struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Serialized as a map with the field names.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(serde = "map")]
+struct Named<T> {
+    pub first: T,
+    pub second: T,
+    pub _marker: PhantomData<T>,
+}
+
+/// Tuple struct with an array field, serialized as a flat sequence.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(serde = "seq")]
+struct Rgba(pub [u8; 3], pub u8);
+
+#[test]
+fn test_seq_tokens() {
+    assert_tokens(&Vec3 { x: 1., y: 2., z: 3. },
+                  &[Token::Tuple { len: 3 },
+                    Token::F32(1.),
+                    Token::F32(2.),
+                    Token::F32(3.),
+                    Token::TupleEnd]);
+}
+
+#[test]
+fn test_seq_json() {
+    let v = Vec3 { x: 1., y: 2., z: 3. };
+    assert_eq!(serde_json::to_string(&v).unwrap(), "[1.0,2.0,3.0]");
+    assert_eq!(serde_json::from_str::<Vec3>("[1.0,2.0,3.0]").unwrap(), v);
+    let rgba = Rgba([1, 2, 3], 4);
+    assert_eq!(serde_json::to_string(&rgba).unwrap(), "[1,2,3,4]");
+    assert_eq!(serde_json::from_str::<Rgba>("[1,2,3,4]").unwrap(), rgba);
+}
+
+#[test]
+fn test_seq_wrong_length() {
+    let err = serde_json::from_str::<Vec3>("[1.0,2.0]").unwrap_err();
+    assert!(err.to_string().contains("invalid length 2, expected an array of length 3"));
+    let err = serde_json::from_str::<Vec3>("[1.0,2.0,3.0,4.0]").unwrap_err();
+    assert!(err.to_string().contains("invalid length 4, expected an array of length 3"));
+}
+
+#[test]
+fn test_map_tokens() {
+    let named = Named { first: "a".to_string(), second: "b".to_string(), _marker: PhantomData };
+    assert_tokens(&named,
+                  &[Token::Struct { name: "Named", len: 2 },
+                    Token::Str("first"),
+                    Token::String("a"),
+                    Token::Str("second"),
+                    Token::String("b"),
+                    Token::StructEnd]);
+    assert_de_tokens(&named,
+                     &[Token::Seq { len: Some(2) },
+                       Token::String("a"),
+                       Token::String("b"),
+                       Token::SeqEnd]);
+}
+
+#[test]
+fn test_map_errors() {
+    assert_de_tokens_error::<Named<i32>>(&[Token::Struct { name: "Named", len: 1 },
+                                           Token::Str("first"),
+                                           Token::I32(1),
+                                           Token::StructEnd],
+                                         "missing field `second`");
+    assert_de_tokens_error::<Named<i32>>(&[Token::Struct { name: "Named", len: 2 },
+                                           Token::Str("first"),
+                                           Token::I32(1),
+                                           Token::Str("first"),
+                                           Token::I32(2)],
+                                         "duplicate field `first`");
+}
+
+#[test]
+fn test_map_json() {
+    let named: Named<i32> = serde_json::from_str(r#"{"second": 2, "first": 1}"#).unwrap();
+    assert_eq!(named, Named { first: 1, second: 2, _marker: PhantomData });
+}
+
+#[test]
+fn test_map_unknown_fields() {
+    let json = r#"{"first": 1, "third": {"nested": [3]}, "second": 2}"#;
+    let named: Named<i32> = serde_json::from_str(json).unwrap();
+    assert_eq!(named, Named { first: 1, second: 2, _marker: PhantomData });
+}
@@ -0,0 +1,104 @@
+extern crate struct_array;
+
+use std::marker::PhantomData;
+use struct_array::{Marker, StructArray};
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+struct World;
+
+/// Struct array with a `PhantomData` marker field, which is skipped
+/// automatically.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Point<F> {
+    pub x: f64,
+    pub y: f64,
+    pub _frame: PhantomData<F>,
+}
+
+/// Zero-sized marker with a default value.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+struct Tag;
+
+unsafe impl Marker for Tag {}
+
+/// Struct array with a private zero-sized field between the elements.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Tagged {
+    pub a: u16,
+    #[struct_array(skip)]
+    tag: Tag,
+    pub b: [u16; 2],
+}
+
+/// Struct array with the same array as `Tagged`.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Triple(pub u16, pub u16, pub u16);
+
+/// Tuple struct array with a `PhantomData` field.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Pair<T>(pub u8, PhantomData<T>, pub u8);
+
+fn point(x: f64, y: f64) -> Point<World> {
+    Point { x, y, _frame: PhantomData }
+}
+
+#[test]
+fn test_len() {
+    assert_eq!(Point::<World>::LEN, 2);
+    assert_eq!(Tagged::LEN, 3);
+    assert_eq!(Pair::<String>::LEN, 2);
+}
+
+#[test]
+fn test_index_consts() {
+    assert_eq!(Tagged::A_INDEX, 0);
+    assert_eq!(Tagged::B_INDEX, 1);
+}
+
+#[test]
+fn test_deref() {
+    let mut p = point(1., 2.);
+    assert_eq!(*p, [1., 2.]);
+    p[1] = 3.;
+    assert_eq!(p, point(1., 3.));
+}
+
+#[test]
+fn test_from_array() {
+    let p: Point<World> = [1., 2.].into();
+    assert_eq!(p, point(1., 2.));
+    let tagged: Tagged = [1, 2, 3].into();
+    assert_eq!(tagged, Tagged { a: 1, tag: Tag, b: [2, 3] });
+    let pair: Pair<String> = [4, 5].into();
+    assert_eq!(pair, Pair(4, PhantomData, 5));
+}
+
+#[test]
+fn test_into_array() {
+    let array: [u16; 3] = Tagged { a: 1, tag: Tag, b: [2, 3] }.into();
+    assert_eq!(array, [1, 2, 3]);
+}
+
+#[test]
+fn test_as_flat_slice() {
+    let points = [point(1., 2.), point(3., 4.)];
+    assert_eq!(Point::as_flat_slice(&points), &[1., 2., 3., 4.]);
+}
+
+#[test]
+fn test_from_slice_ref() {
+    let slice = &[1, 2, 3][..];
+    let tagged: &Tagged = slice.into();
+    assert_eq!(tagged.b, [2, 3]);
+}
+
+#[test]
+fn test_cast() {
+    let tagged: Tagged = Triple(1, 2, 3).cast();
+    assert_eq!(tagged, Tagged { a: 1, tag: Tag, b: [2, 3] });
+    assert_eq!(tagged.cast::<Triple>(), Triple(1, 2, 3));
+}
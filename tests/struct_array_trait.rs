@@ -0,0 +1,113 @@
+#[macro_use]
+extern crate struct_array;
+
+use struct_array::StructArray;
+
+/// Example struct array.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Example {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+/// Example struct array deriving only `StructArrayConvert`.
+#[derive(Clone,Debug,PartialEq,StructArrayConvert)]
+#[repr(C)]
+struct ConvertOnly(pub f32, pub f32);
+
+/// Example struct array deriving both `StructArrayDeref` and
+/// `StructArrayConvert`, without the `StructArray` trait.
+#[derive(Clone,Debug,PartialEq,StructArrayDeref,StructArrayConvert)]
+#[repr(C)]
+struct Separate {
+    pub a: i8,
+    pub b: i8,
+}
+
+fn sum<S: StructArray<Elem = u32>>(s: &S) -> u32 {
+    s.iter().sum()
+}
+
+#[test]
+fn test_len() {
+    assert_eq!(Example::LEN, 3);
+}
+
+#[test]
+fn test_generic_elem() {
+    assert_eq!(sum(&Example { x: 1, y: 2, z: 3 }), 6);
+}
+
+#[test]
+fn test_as_array() {
+    let mut example = Example { x: 1, y: 2, z: 3 };
+    assert_eq!(example.as_array(), &[1, 2, 3]);
+    example.as_array_mut()[2] = 4;
+    assert_eq!(example, Example { x: 1, y: 2, z: 4 });
+}
+
+#[test]
+fn test_as_slice() {
+    let mut example = Example { x: 1, y: 2, z: 3 };
+    assert_eq!(example.as_slice(), &[1, 2, 3]);
+    example.as_mut_slice()[0] = 4;
+    assert_eq!(example, Example { x: 4, y: 2, z: 3 });
+}
+
+#[test]
+fn test_convert_only() {
+    let mut convert_only = ConvertOnly(1., 2.);
+    assert_eq!(AsRef::<[f32]>::as_ref(&convert_only), &[1., 2.]);
+    AsMut::<[f32]>::as_mut(&mut convert_only)[0] = 3.;
+    assert_eq!(<[f32; 2]>::from(convert_only), [3., 2.]);
+}
+
+#[test]
+fn test_separate_derives() {
+    let mut separate = Separate::from([1, 2]);
+    separate[1] = 3;
+    assert_eq!(*separate, [1, 3]);
+    let slice: &[i8] = (&separate).into();
+    assert_eq!(slice, &[1, 3]);
+}
+
+#[test]
+fn test_iter_mut() {
+    let mut example = Example { x: 1, y: 2, z: 3 };
+    for elem in example.iter_mut() {
+        *elem += 1;
+    }
+    assert_eq!(example, Example { x: 2, y: 3, z: 4 });
+}
+
+#[test]
+fn test_map_in_place() {
+    let mut example = Example { x: 1, y: 2, z: 3 };
+    example.map_in_place(|&elem| elem * 10);
+    assert_eq!(example, Example { x: 10, y: 20, z: 30 });
+}
+
+#[test]
+fn test_as_flat_slice() {
+    let mut examples = [Example { x: 1, y: 2, z: 3 }, Example { x: 4, y: 5, z: 6 }];
+    assert_eq!(Example::as_flat_slice(&examples), &[1, 2, 3, 4, 5, 6]);
+    Example::as_flat_slice_mut(&mut examples)[4] = 0;
+    assert_eq!(examples[1], Example { x: 4, y: 0, z: 6 });
+}
+
+#[test]
+fn test_from_flat_slice() {
+    let mut flat = [1, 2, 3, 4, 5, 6];
+    assert_eq!(Example::from_flat_slice(&flat),
+               &[Example { x: 1, y: 2, z: 3 }, Example { x: 4, y: 5, z: 6 }]);
+    Example::from_flat_slice_mut(&mut flat)[0].y = 0;
+    assert_eq!(flat, [1, 0, 3, 4, 5, 6]);
+}
+
+#[test]
+#[should_panic]
+fn test_from_flat_slice_wrong_len() {
+    Example::from_flat_slice(&[1, 2, 3, 4]);
+}
@@ -0,0 +1,151 @@
+#[macro_use]
+extern crate struct_array;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Vector whose swizzles of two and three fields return `Vec2` and `Vec3`.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(swizzle(len2 = "Vec2", len3 = "Vec3"))]
+struct Vec4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+/// Color with swizzles of at most two fields, returned as arrays.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(swizzle(max_len = 2))]
+struct Rgb {
+    pub r: String,
+    pub g: String,
+    pub b: String,
+}
+
+/// Swizzles of fields with longer names.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(swizzle(max_len = 2))]
+struct Range {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Swizzles some of which would be keywords, like `if`, `fn` and `as`.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(swizzle(max_len = 2))]
+struct Letters {
+    pub i: u8,
+    pub f: u8,
+    pub n: u8,
+    pub a: u8,
+    pub s: u8,
+}
+
+/// Swizzles some of which have ambiguous names, like `a_b_c` from `a` and
+/// `b_c` or from `a_b` and `c`.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(swizzle(max_len = 2))]
+struct Joined {
+    pub a: u8,
+    pub b_c: u8,
+    pub a_b: u8,
+    pub c: u8,
+}
+
+/// Methods with the ambiguous names, which would conflict with the swizzles
+/// if those were generated.
+impl Joined {
+    fn a_b_c(&self) -> &'static str {
+        "ambiguous"
+    }
+
+    fn set_a_b_c(&mut self) {}
+}
+
+fn vec4() -> Vec4 {
+    Vec4 { x: 1., y: 2., z: 3., w: 4. }
+}
+
+fn rgb() -> Rgb {
+    Rgb { r: "r".to_string(), g: "g".to_string(), b: "b".to_string() }
+}
+
+#[test]
+fn test_getters_with_targets() {
+    let v = vec4();
+    assert_eq!(v.xy(), Vec2 { x: 1., y: 2. });
+    assert_eq!(v.wz(), Vec2 { x: 4., y: 3. });
+    assert_eq!(v.zyx(), Vec3 { x: 3., y: 2., z: 1. });
+    assert_eq!(v.xxx(), Vec3 { x: 1., y: 1., z: 1. });
+    assert_eq!(v.wzyx(), [4., 3., 2., 1.]);
+    assert_eq!(v.xxxx(), [1., 1., 1., 1.]);
+}
+
+#[test]
+fn test_setters() {
+    let mut v = vec4();
+    v.set_wx([5., 6.]);
+    assert_eq!(v, Vec4 { x: 6., y: 2., z: 3., w: 5. });
+    v.set_xyz(Vec3 { x: 7., y: 8., z: 9. });
+    assert_eq!(v, Vec4 { x: 7., y: 8., z: 9., w: 5. });
+    v.set_wzyx([1., 2., 3., 4.]);
+    assert_eq!(v, Vec4 { x: 4., y: 3., z: 2., w: 1. });
+}
+
+#[test]
+fn test_clone_elements() {
+    let mut c = rgb();
+    assert_eq!(c.bg(), ["b".to_string(), "g".to_string()]);
+    assert_eq!(c.rr(), ["r".to_string(), "r".to_string()]);
+    c.set_gb(["x".to_string(), "y".to_string()]);
+    assert_eq!(c.g, "x");
+    assert_eq!(c.b, "y");
+}
+
+#[test]
+fn test_long_field_names() {
+    let mut range = Range { start: 1, end: 2 };
+    assert_eq!(range.end_start(), [2, 1]);
+    range.set_end_start([3, 4]);
+    assert_eq!(range, Range { start: 4, end: 3 });
+}
+
+#[test]
+fn test_keyword_names() {
+    let mut letters = Letters { i: 1, f: 2, n: 3, a: 4, s: 5 };
+    assert_eq!(letters.fi(), [2, 1]);
+    assert_eq!(letters.nf(), [3, 2]);
+    assert_eq!(letters.sa(), [5, 4]);
+    letters.set_if([6, 7]);
+    letters.set_as([8, 9]);
+    assert_eq!(letters, Letters { i: 6, f: 7, n: 3, a: 8, s: 9 });
+}
+
+#[test]
+fn test_ambiguous_names() {
+    let mut joined = Joined { a: 1, b_c: 2, a_b: 3, c: 4 };
+    assert_eq!(joined.a_b_c(), "ambiguous");
+    joined.set_a_b_c();
+    assert_eq!(joined.c_a(), [4, 1]);
+    assert_eq!(joined.b_c_a_b(), [2, 3]);
+    joined.set_a_b_a([5, 6]);
+    assert_eq!(joined, Joined { a: 6, b_c: 2, a_b: 5, c: 4 });
+}
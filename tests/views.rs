@@ -0,0 +1,66 @@
+#[macro_use]
+extern crate struct_array;
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Vector viewable as its first three fields, or its middle two.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(view(Vec3 = "x..=z", Vec2 = "y..w"))]
+struct Vec4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Rgb(pub u8, pub u8, pub u8);
+
+/// Tuple struct viewable by field indices, with an array field.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(view(Rgb = "1.."))]
+struct LabeledRgb(pub u8, pub [u8; 3]);
+
+#[test]
+fn test_view() {
+    let v = Vec4 { x: 1., y: 2., z: 3., w: 4. };
+    assert_eq!(v.as_vec3(), &Vec3 { x: 1., y: 2., z: 3. });
+}
+
+#[test]
+fn test_view_mut() {
+    let mut v = Vec4 { x: 1., y: 2., z: 3., w: 4. };
+    v.as_vec3_mut().z = 5.;
+    assert_eq!(v.z, 5.);
+}
+
+#[test]
+fn test_exclusive_range_view() {
+    let mut v = Vec4 { x: 1., y: 2., z: 3., w: 4. };
+    assert_eq!(v.as_vec2(), &Vec2 { x: 2., y: 3. });
+    v.as_vec2_mut().x = 0.;
+    assert_eq!(v.y, 0.);
+}
+
+#[test]
+fn test_tuple_struct_view() {
+    let mut c = LabeledRgb(0, [1, 2, 3]);
+    c.as_rgb_mut().2 = 4;
+    assert_eq!(c.as_rgb(), &Rgb(1, 2, 4));
+}
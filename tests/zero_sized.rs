@@ -0,0 +1,98 @@
+extern crate struct_array;
+
+use std::marker::PhantomData;
+use struct_array::StructArray;
+
+/// Struct array with no fields.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(elem = "f32")]
+struct NoChannels {}
+
+/// Unit struct array.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(elem = "u64")]
+struct Unit;
+
+/// Generic struct array with no elements, only a marker.
+#[derive(Clone,Debug,PartialEq,StructArray)]
+#[repr(C)]
+#[struct_array(elem = "T")]
+struct Empty<T> {
+    _marker: PhantomData<T>,
+}
+
+/// Struct array of zero-sized elements.
+#[derive(Clone,Copy,Debug,PartialEq,StructArray)]
+#[repr(C)]
+struct Units {
+    pub a: (),
+    pub b: (),
+}
+
+#[test]
+fn test_len() {
+    assert_eq!(NoChannels::LEN, 0);
+    assert_eq!(Unit::LEN, 0);
+    assert_eq!(Empty::<String>::LEN, 0);
+    assert_eq!(Units::LEN, 2);
+}
+
+#[test]
+fn test_zero_fields_views() {
+    let mut channels = NoChannels {};
+    let empty: [f32; 0] = [];
+    assert_eq!(*channels, empty);
+    assert!(channels.as_slice().is_empty());
+    assert!(channels.as_mut_slice().is_empty());
+    let array: &[u64; 0] = Unit.as_ref();
+    assert_eq!(array.as_ptr() as usize % std::mem::align_of::<u64>(), 0);
+}
+
+#[test]
+fn test_zero_fields_by_value() {
+    let array: [f32; 0] = NoChannels {}.into();
+    assert_eq!(array.len(), 0);
+    let unit: Unit = [].into();
+    assert_eq!(unit, Unit);
+    let empty: Empty<String> = [].into();
+    assert_eq!(empty, Empty { _marker: PhantomData });
+}
+
+#[test]
+fn test_zero_fields_from_slice() {
+    let slice: &[f32] = &[];
+    let channels: &NoChannels = slice.into();
+    assert_eq!(channels, &NoChannels {});
+}
+
+#[test]
+fn test_zero_fields_flat_slices() {
+    let channels = [NoChannels {}, NoChannels {}];
+    assert!(NoChannels::as_flat_slice(&channels).is_empty());
+    assert!(NoChannels::from_flat_slice(&[]).is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_zero_fields_from_nonempty_flat_slice() {
+    NoChannels::from_flat_slice(&[1.]);
+}
+
+#[test]
+fn test_zero_sized_elems() {
+    let mut units = Units { a: (), b: () };
+    assert_eq!(units.as_slice().len(), 2);
+    units[1] = ();
+    let array: [(); 2] = units.into();
+    assert_eq!(array.len(), 2);
+    assert_eq!(Units::from_flat_slice(&[(); 6]).len(), 3);
+    assert_eq!(Units::as_flat_slice(&[units; 4]).len(), 8);
+}
+
+#[test]
+#[should_panic]
+fn test_zero_sized_elems_from_flat_slice_wrong_len() {
+    Units::from_flat_slice(&[(); 3]);
+}
@@ -0,0 +1,21 @@
+extern crate struct_array;
+extern crate zerocopy;
+
+use struct_array::StructArray;
+use zerocopy::{Immutable, IntoBytes};
+
+#[derive(Clone,Copy,Debug,PartialEq,StructArray,IntoBytes,Immutable)]
+#[repr(C)]
+struct Header {
+    pub src_port: u16,
+    pub dst_port: u16,
+    pub length: u16,
+    pub checksum: u16,
+}
+
+#[test]
+fn test_byte_views_with_struct_array_in_scope() {
+    let header = Header { src_port: 1, dst_port: 2, length: 3, checksum: 4 };
+    assert_eq!(IntoBytes::as_bytes(&header), header.as_ne_bytes());
+    assert_eq!(Header::try_from_bytes(header.as_bytes()), Ok(&header));
+}